@@ -0,0 +1,85 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use crate::MapFile;
+
+// ~1% false positives at 10 bits and 7 probes per name
+const BITS_PER_NAME: usize = 10;
+const PROBES: u64 = 7;
+
+/// a bloom filter over every symbol name in a map.
+///
+/// `might_contain` never says no to a name that's in the map, but says yes to roughly 1% of
+/// names that aren't. use it to throw out most misses cheaply before doing a real lookup,
+/// not as the lookup itself
+pub struct NameBloom {
+    bits: Vec<u64>,
+}
+
+impl NameBloom {
+    fn with_capacity(names: usize) -> Self {
+        let bits = (names * BITS_PER_NAME).max(64);
+
+        NameBloom {
+            bits: vec![0; bits.div_ceil(64)],
+        }
+    }
+
+    fn len_bits(&self) -> u64 {
+        self.bits.len() as u64 * 64
+    }
+
+    /// double hashing: the probes are `h1 + i * h2`, both halves of one 64 bit hash
+    fn probes(len: u64, name: &str) -> impl Iterator<Item = u64> {
+        let mut hasher = DefaultHasher::new();
+        name.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        let (h1, h2) = (hash & 0xffff_ffff, hash >> 32);
+
+        (0..PROBES).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % len)
+    }
+
+    fn insert(&mut self, name: &str) {
+        for bit in Self::probes(self.len_bits(), name) {
+            self.bits[(bit / 64) as usize] |= 1 << (bit % 64);
+        }
+    }
+
+    pub fn might_contain(&self, name: &str) -> bool {
+        Self::probes(self.len_bits(), name)
+            .all(|bit| self.bits[(bit / 64) as usize] & (1 << (bit % 64)) != 0)
+    }
+}
+
+impl<'a> MapFile<'a> {
+    /// builds a bloom filter over the mangled names of all publics and static symbols
+    pub fn name_bloom(&self) -> NameBloom {
+        let mut bloom = NameBloom::with_capacity(self.functions.len() + self.static_symbols.len());
+
+        for symbol in self.symbols_by_rva() {
+            bloom.insert(symbol.symbol());
+        }
+
+        bloom
+    }
+}
+
+#[test]
+fn name_bloom() {
+    let map_data = std::fs::read("csgo-x86.map").unwrap();
+    let map_string = String::from_utf8(map_data).unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+    let bloom = map.name_bloom();
+
+    assert!(map
+        .symbols_by_rva()
+        .all(|x| bloom.might_contain(x.symbol())));
+
+    let false_positives = (0..10000)
+        .filter(|i| bloom.might_contain(&format!("_absent_symbol_{}", i)))
+        .count();
+
+    assert!(false_positives < 300, "{} false positives", false_positives);
+}
@@ -0,0 +1,119 @@
+use std::fmt;
+
+use crate::{MapFile, SymbolRef};
+
+/// what changed between two builds, see [`MapFile::diff`]
+#[derive(Debug)]
+pub struct MapDiff<'m> {
+    /// only in the newer map, in its map order
+    pub added: Vec<SymbolRef<'m>>,
+    /// only in the older map, in its map order
+    pub removed: Vec<SymbolRef<'m>>,
+    /// `(old, new)` for symbols whose rva changed, in the newer map's order
+    pub moved: Vec<(SymbolRef<'m>, SymbolRef<'m>)>,
+    /// symbols in both maps at the same rva
+    pub unchanged: usize,
+}
+
+impl<'a> MapFile<'a> {
+    /// compares this map with a later build of the same module, matching publics and statics
+    /// by mangled name. where a name repeats, only the one [`MapFile::symbol_named`] finds
+    /// takes part
+    pub fn diff<'m>(&'m self, other: &'m MapFile) -> MapDiff<'m> {
+        let old = self.name_index();
+        let new = other.name_index();
+
+        let mut diff = MapDiff {
+            added: Vec::new(),
+            removed: Vec::new(),
+            moved: Vec::new(),
+            unchanged: 0,
+        };
+
+        for symbol in other.symbols() {
+            if !std::ptr::eq(new[symbol.symbol()].get(), symbol.get()) {
+                continue;
+            }
+
+            match old.get(symbol.symbol()) {
+                None => diff.added.push(symbol),
+                Some(previous) if previous.rva() != symbol.rva() => {
+                    diff.moved.push((*previous, symbol))
+                }
+                Some(_) => diff.unchanged += 1,
+            }
+        }
+
+        for symbol in self.symbols() {
+            if std::ptr::eq(old[symbol.symbol()].get(), symbol.get())
+                && !new.contains_key(symbol.symbol())
+            {
+                diff.removed.push(symbol);
+            }
+        }
+
+        diff
+    }
+}
+
+impl fmt::Display for MapDiff<'_> {
+    /// one `+`, `-` or `~` line per change, then the totals
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for symbol in &self.added {
+            writeln!(f, "+ {} {:#x}", symbol.symbol(), symbol.rva())?;
+        }
+
+        for symbol in &self.removed {
+            writeln!(f, "- {} {:#x}", symbol.symbol(), symbol.rva())?;
+        }
+
+        for (old, new) in &self.moved {
+            writeln!(f, "~ {} {:#x} -> {:#x}", new.symbol(), old.rva(), new.rva())?;
+        }
+
+        writeln!(
+            f,
+            "{} added, {} removed, {} moved, {} unchanged",
+            self.added.len(),
+            self.removed.len(),
+            self.moved.len(),
+            self.unchanged
+        )
+    }
+}
+
+#[test]
+fn diff() {
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+    let old = MapFile::load(&map_string).unwrap();
+
+    let map_string = map_string
+        .replace(
+            "_shutdown                  10001080",
+            "_teardown                  10001080",
+        )
+        .replace(
+            "0001:00000060       _local_helper              10001060",
+            "0001:00000070       _local_helper              10001070",
+        );
+    let new = MapFile::load(&map_string).unwrap();
+
+    let diff = old.diff(&new);
+
+    assert_eq!(diff.added.len(), 1);
+    assert_eq!(diff.added[0].symbol(), "_teardown");
+    assert_eq!(diff.removed.len(), 1);
+    assert_eq!(diff.removed[0].symbol(), "_shutdown");
+    assert_eq!(diff.moved.len(), 1);
+    assert_eq!(diff.unchanged, 5);
+
+    assert_eq!(
+        diff.to_string(),
+        "+ _teardown 0x1080\n\
+         - _shutdown 0x1080\n\
+         ~ _local_helper 0x1060 -> 0x1070\n\
+         1 added, 1 removed, 1 moved, 5 unchanged\n"
+    );
+
+    assert_eq!(old.diff(&old).unchanged, 7);
+}
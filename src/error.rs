@@ -0,0 +1,185 @@
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use core::fmt;
+use core::ops::Range;
+
+use crate::{MapFile, MapParseError};
+
+/// tags the spots in the parser whose failures [`ParseError`] has a variant for. it is attached
+/// as the anyhow context and prints the same message the parser always had
+#[derive(Debug)]
+pub(crate) enum Failure {
+    /// a `<seg>:<addr>` or rva column that doesn't parse
+    BadAddress(&'static str),
+    /// a header line that never showed up
+    MissingHeaderField(&'static str),
+    /// a section row with an unknown class, or `None` if the row had no class column
+    SectionClass(Option<String>),
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Failure::BadAddress(message) => f.write_str(message),
+            Failure::MissingHeaderField(field) => write!(f, "{} not found", field),
+            Failure::SectionClass(Some(class)) => write!(f, "unexpected section class `{}`", class),
+            Failure::SectionClass(None) => f.write_str("no class was found"),
+        }
+    }
+}
+
+impl core::error::Error for Failure {}
+
+/// why loading a map failed. every entry point returns this, so callers can decide what to do by
+/// the kind of failure instead of by message
+#[derive(Debug, thiserror::Error)]
+pub enum ParseError {
+    /// an address or rva column that isn't a number
+    #[error("malformed address `{token}` (line {line})")]
+    BadAddress {
+        line: usize,
+        /// where `token` starts in the input
+        byte_offset: usize,
+        token: String,
+    },
+    /// the module name, timestamp or preferred load address line is missing. `line` is where
+    /// it was needed, which is past the end of the input if nothing needed it sooner
+    #[error("{field} not found (line {line})")]
+    MissingHeaderField {
+        line: usize,
+        /// where the line starts in the input
+        byte_offset: usize,
+        field: &'static str,
+    },
+    /// a section row with a class that isn't allowed, outside of lenient parsing. `class` is
+    /// `None` if the row had no class column at all
+    #[error("{} (line {line})", Failure::SectionClass(class.clone()))]
+    UnexpectedSectionClass {
+        line: usize,
+        /// where the row starts in the input
+        byte_offset: usize,
+        /// length of the row
+        len: usize,
+        class: Option<String>,
+    },
+    /// anything else, with the position of the offending token
+    #[error(transparent)]
+    Other(#[from] MapParseError),
+}
+
+impl ParseError {
+    pub(crate) fn new(error: MapParseError, input: &str) -> Self {
+        let token = input
+            .get(error.byte_offset..error.byte_offset + error.len)
+            .unwrap_or_default();
+
+        Self::with_token(error, token)
+    }
+
+    /// like [`ParseError::new`], for callers that only have the offending token at hand
+    pub(crate) fn with_token(error: MapParseError, token: &str) -> Self {
+        match error.error.downcast_ref::<Failure>() {
+            Some(Failure::BadAddress(_)) => ParseError::BadAddress {
+                line: error.line,
+                byte_offset: error.byte_offset,
+                token: token.to_owned(),
+            },
+            Some(Failure::MissingHeaderField(field)) => ParseError::MissingHeaderField {
+                line: error.line,
+                byte_offset: error.byte_offset,
+                field,
+            },
+            Some(Failure::SectionClass(class)) => ParseError::UnexpectedSectionClass {
+                line: error.line,
+                byte_offset: error.byte_offset,
+                len: error.len,
+                class: class.clone(),
+            },
+            None => ParseError::Other(error),
+        }
+    }
+
+    /// 1-based line the error is on
+    pub fn line(&self) -> usize {
+        match self {
+            ParseError::BadAddress { line, .. }
+            | ParseError::MissingHeaderField { line, .. }
+            | ParseError::UnexpectedSectionClass { line, .. } => *line,
+            ParseError::Other(error) => error.line,
+        }
+    }
+
+    /// byte range of the offending token in the input. empty for a missing header field
+    pub fn span(&self) -> Range<usize> {
+        match self {
+            ParseError::BadAddress {
+                byte_offset, token, ..
+            } => *byte_offset..*byte_offset + token.len(),
+            ParseError::MissingHeaderField { byte_offset, .. } => *byte_offset..*byte_offset,
+            ParseError::UnexpectedSectionClass {
+                byte_offset, len, ..
+            } => *byte_offset..*byte_offset + len,
+            ParseError::Other(error) => error.byte_offset..error.byte_offset + error.len,
+        }
+    }
+}
+
+impl<'a> MapFile<'a> {
+    /// same as [`MapFile::load`], kept from when `load` had an error type of its own
+    pub fn parse(input: &'a str) -> Result<Self, ParseError> {
+        Self::load(input)
+    }
+}
+
+#[test]
+fn parse_error_kinds() {
+    let map_string = std::fs::read_to_string("fixtures/mapinfo-exports.map").unwrap();
+
+    assert!(MapFile::parse(&map_string).is_ok());
+
+    let error = MapFile::parse(&map_string.replace("0001:00000040", "0001:xyz"))
+        .err()
+        .unwrap();
+    assert!(matches!(
+        &error,
+        ParseError::BadAddress { line: 16, token, .. } if token == "0001:xyz"
+    ));
+
+    let error = MapFile::parse(&map_string.replace(" 10001100 f", " 1000110g f"))
+        .err()
+        .unwrap();
+    assert!(matches!(
+        &error,
+        ParseError::BadAddress { line: 17, token, .. } if token == "1000110g"
+    ));
+
+    let error = MapFile::parse(&map_string.replace(".rdata                  DATA", ".rdata"))
+        .err()
+        .unwrap();
+    assert!(matches!(
+        &error,
+        ParseError::UnexpectedSectionClass {
+            line: 9,
+            class: None,
+            ..
+        }
+    ));
+
+    let error = MapFile::parse(&map_string.replace(" Preferred load address is 10000000", ""))
+        .err()
+        .unwrap();
+    assert!(matches!(
+        error,
+        ParseError::MissingHeaderField {
+            field: "preferred load address",
+            ..
+        }
+    ));
+
+    // below the load address, which has no variant of its own
+    let error = MapFile::parse(&map_string.replace(" 10001040 f", " 00001040 f"))
+        .err()
+        .unwrap();
+    assert!(matches!(error, ParseError::Other(_)));
+    assert_eq!(error.line(), 16);
+}
@@ -0,0 +1,910 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::ops::Range;
+
+use msvc_demangler::DemangleFlags;
+
+use crate::{
+    Class, GhidraSanitizer, IdaSanitizer, LibObject, MapFile, MergeByRva, NameSanitizer,
+    SymbolFlags, SymbolKind, SymbolRef, VerbatimSanitizer, X64dbgSanitizer,
+};
+
+/// how exporters print addresses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressFormat {
+    /// `268439552`
+    Decimal,
+    /// `10001000`
+    Hex,
+    /// `0x10001000`
+    HexPrefixed,
+}
+
+impl AddressFormat {
+    pub fn format(self, addr: usize) -> String {
+        match self {
+            AddressFormat::Decimal => addr.to_string(),
+            AddressFormat::Hex => format!("{:x}", addr),
+            AddressFormat::HexPrefixed => format!("{:#x}", addr),
+        }
+    }
+}
+
+/// what to do with mangled names
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DemangleMode {
+    /// only keep symbols that demangle. the rest (plain C names, and anything the demangler
+    /// rejects) is left out of every exporter, [`MapFile::demangle_failures`] lists them along
+    /// with why
+    Always,
+    /// keep every name as it appears in the map
+    Never,
+    /// demangle what can be, keeping the raw name for the rest
+    #[default]
+    Lenient,
+}
+
+/// which label the IDA exporter keeps when several symbols share an address (aliases, folded
+/// COMDATs, thunks), as IDA rejects more than one
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DedupPolicy {
+    /// write every symbol, duplicate addresses and all
+    #[default]
+    KeepAll,
+    /// the first one in address order, i.e. publics before statics, then by mangled name
+    First,
+    /// the one with the longest exported name, which tends to be the most specific. ties go to
+    /// the first one
+    LongestName,
+}
+
+/// knobs for the symbol exporters
+#[derive(Default)]
+pub struct ExportOptions<'a> {
+    /// mangled symbol -> replacement name, applied before the name is sanitized for the target tool.
+    /// symbols not in here keep their (demangled) name
+    pub renames: HashMap<&'a str, String>,
+    /// leave out symbols living in linker-synthesized sections, like anonymous COMDATs
+    pub skip_synthetic_sections: bool,
+    /// naming rules to apply instead of the exporter's own
+    pub sanitizer: Option<&'a dyn NameSanitizer>,
+    /// address style to use instead of the exporter's own
+    pub address_format: Option<AddressFormat>,
+    /// text to put in place of the demangler's `` `anonymous namespace' ``, e.g. `(anon)`,
+    /// before the name is sanitized
+    pub anonymous_namespace: Option<&'a str>,
+    /// how much of the signature to demangle instead of the exporter's own, e.g.
+    /// [`DemangleFlags::COMPLETE`] to keep argument and return types
+    pub demangle_flags: Option<DemangleFlags>,
+    /// whether to demangle at all, and what to do with names that don't demangle
+    pub demangle: DemangleMode,
+    /// what to do about symbols sharing an address
+    pub dedup: DedupPolicy,
+    /// only export symbols with an rva in this range, see [`MapFile::symbols_in_range`]
+    pub rva_range: Option<Range<usize>>,
+}
+
+fn fix_mangled_symbol(sym: &str) -> String {
+    sym.chars()
+        .map(|x| match "<>".contains(x) {
+            true => '_',
+            false => x,
+        })
+        .collect()
+}
+
+/// the name to export for `symbol`, or the demangler's error if [`DemangleMode::Always`] leaves
+/// it out
+fn demangle_name(symbol: &str, options: &ExportOptions) -> msvc_demangler::Result<String> {
+    let flags = options.demangle_flags.unwrap_or(DemangleFlags::NAME_ONLY);
+
+    let mut name = match options.demangle {
+        DemangleMode::Always => msvc_demangler::demangle(symbol, flags)?,
+        DemangleMode::Never => symbol.to_owned(),
+        DemangleMode::Lenient => {
+            msvc_demangler::demangle(symbol, flags).unwrap_or(symbol.to_owned())
+        }
+    };
+
+    if let Some(anonymous_namespace) = options.anonymous_namespace {
+        name = name.replace("`anonymous namespace'", anonymous_namespace);
+    }
+
+    Ok(name)
+}
+
+/// quotes `text` as a json string
+fn json_string(text: &str) -> String {
+    let mut output = String::with_capacity(text.len() + 2);
+    output.push('"');
+
+    for x in text.chars() {
+        match x {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            x if x.is_control() => output.push_str(&format!("\\u{:04x}", x as u32)),
+            x => output.push(x),
+        }
+    }
+
+    output.push('"');
+    output
+}
+
+impl<'a> MapFile<'a> {
+    /// the symbols an exporter looks at, narrowed down by `options`, in
+    /// [`MapFile::sorted_functions`] order with publics ahead of statics on the same rva
+    fn export_candidates<'m>(
+        &'m self,
+        options: &'m ExportOptions,
+    ) -> impl Iterator<Item = SymbolRef<'m>> {
+        let range = options.rva_range.clone().unwrap_or(0..usize::MAX);
+        let in_range = move |x: &SymbolRef| range.contains(&x.rva());
+
+        MergeByRva::new(
+            self.sorted_functions()
+                .into_iter()
+                .map(SymbolRef::Function)
+                .filter(in_range.clone()),
+            self.sorted_static_symbols()
+                .into_iter()
+                .map(SymbolRef::Static)
+                .filter(in_range),
+        )
+        .filter(move |symbol| {
+            !(options.skip_synthetic_sections
+                && self
+                    .section_containing(symbol.addr())
+                    .is_some_and(|x| x.is_synthetic()))
+        })
+    }
+
+    /// every symbol's va and name as the exporters would demangle it with `options`, in address
+    /// order. renames and sanitizing are left to the caller, and symbols that
+    /// [`DemangleMode::Always`] leaves out are in [`MapFile::demangle_failures`] instead
+    pub fn demangled_symbols(&self, options: &ExportOptions) -> Vec<(usize, String)> {
+        self.export_candidates(options)
+            .filter_map(|symbol| {
+                Some((
+                    symbol.rva_plus_base(self),
+                    demangle_name(symbol.symbol(), options).ok()?,
+                ))
+            })
+            .collect()
+    }
+
+    /// the symbols the exporters leave out because they don't demangle, with the demangler's
+    /// error, in address order. always empty unless `options` asks for
+    /// [`DemangleMode::Always`]. the IDA exporter still writes the ones in `options.renames`
+    pub fn demangle_failures<'m>(
+        &'m self,
+        options: &'m ExportOptions,
+    ) -> Vec<(SymbolRef<'m>, String)> {
+        self.export_candidates(options)
+            .filter_map(|symbol| {
+                let error = demangle_name(symbol.symbol(), options).err()?;
+                Some((symbol, error.to_string()))
+            })
+            .collect()
+    }
+
+    /// produces the `.sym` file consumed by `ida_parse.py`.
+    ///
+    /// every line is `<va> <name> <label>`, where the label is what actually ends up in IDA.
+    /// like every exporter that writes vas, it writes rvas for lld-link `/lldmap` maps, see
+    /// [`MapFormat::Lld`](crate::MapFormat::Lld)
+    pub fn export_ida(&self, options: &ExportOptions) -> String {
+        let mut output: Vec<u8> = Default::default();
+
+        self.write_ida(&mut output, options)
+            .expect("writing to a vec can't fail");
+
+        String::from_utf8(output).expect("the exporter only writes utf-8")
+    }
+
+    /// streams the `.sym` file to `output` in address order, one symbol at a time,
+    /// so huge maps don't need the whole export buffered in memory
+    pub fn write_ida<W: Write>(&self, mut output: W, options: &ExportOptions) -> io::Result<()> {
+        let sanitizer = options.sanitizer.unwrap_or(&IdaSanitizer);
+        // ida_parse.py reads the address with int()
+        let address_format = options.address_format.unwrap_or(AddressFormat::Decimal);
+
+        // the label picked so far for the address being looked at: va, name and mangled name.
+        // symbols come in address order, so it is written once the address changes
+        let mut pending: Option<(usize, String, Cow<str>)> = None;
+
+        let mut write = |(va, name, label): (usize, String, Cow<str>)| {
+            writeln!(
+                output,
+                "{} {} {}",
+                address_format.format(va),
+                sanitizer.sanitize(&name),
+                fix_mangled_symbol(&label)
+            )
+        };
+
+        for symbol in self.export_candidates(options) {
+            let symbol_name = symbol.symbol();
+
+            // a rename ends up in IDA as it is, so it has to follow IDA's naming rules too
+            let (name, label) = match options.renames.get(symbol_name) {
+                Some(renamed) => (renamed.to_owned(), Cow::Owned(sanitizer.sanitize(renamed))),
+                None => match demangle_name(symbol_name, options) {
+                    Ok(name) => (name, Cow::Borrowed(symbol_name)),
+                    Err(_) => continue,
+                },
+            };
+
+            let va = symbol.rva_plus_base(self);
+
+            match &pending {
+                Some((pending_va, pending_name, _))
+                    if options.dedup != DedupPolicy::KeepAll && *pending_va == va =>
+                {
+                    if options.dedup == DedupPolicy::LongestName && name.len() > pending_name.len()
+                    {
+                        pending = Some((va, name, label));
+                    }
+                }
+                _ => {
+                    if let Some(entry) = pending.replace((va, name, label)) {
+                        write(entry)?;
+                    }
+                }
+            }
+        }
+
+        if let Some(entry) = pending {
+            write(entry)?;
+        }
+
+        Ok(())
+    }
+
+    /// produces an IDAPython script that defines a function for every symbol in a code section,
+    /// spanning up to the next symbol (or the end of its section), as names alone don't give IDA
+    /// any boundaries. run it before importing names with `ida_parse.py`
+    pub fn export_ida_functions(&self) -> String {
+        let mut output = String::from("import ida_funcs\n\n");
+        let mut last_start = None;
+
+        for (symbol, size) in self.inferred_sizes() {
+            let start = symbol.rva_plus_base(self);
+
+            // aliases share one body, only define it once
+            if size == 0 || last_start == Some(start) {
+                continue;
+            }
+
+            if !matches!(self.class_of(symbol.addr()), Some(Class::Code)) {
+                continue;
+            }
+
+            last_start = Some(start);
+
+            output.push_str(
+                format!("ida_funcs.add_func({:#x}, {:#x})\n", start, start + size).as_str(),
+            );
+        }
+
+        output
+    }
+
+    /// produces a breakpad `.sym` file with a `PUBLIC` record per public symbol.
+    ///
+    /// the map doesn't carry the pdb guid/age, so the module id is left zeroed and has
+    /// to be patched in by the caller if their symbol store keys on it
+    pub fn export_breakpad(&self) -> String {
+        let mut output = format!(
+            "MODULE windows x86 {} {}.pdb\n",
+            "0".repeat(33),
+            self.file_name
+        );
+
+        for function in self.sorted_functions() {
+            if matches!(function.libobj, LibObject::Absolute) {
+                continue;
+            }
+
+            output.push_str(
+                format!(
+                    "PUBLIC {:x} 0 {}\n",
+                    function.rva.0,
+                    msvc_demangler::demangle(&function.symbol, DemangleFlags::COMPLETE)
+                        .unwrap_or(function.symbol.to_string())
+                )
+                .as_str(),
+            );
+        }
+
+        output
+    }
+
+    /// produces an x64dbg database (`.dd32`/`.dd64`) with a label for every placed symbol,
+    /// importable with `dbload`. the module is the map's `file_name`, with `.dll` added if
+    /// it has no extension
+    pub fn export_x64dbg(&self, options: &ExportOptions) -> String {
+        let sanitizer = options.sanitizer.unwrap_or(&X64dbgSanitizer);
+        let module = match self.file_name.contains('.') {
+            true => self.file_name.to_lowercase(),
+            false => format!("{}.dll", self.file_name.to_lowercase()),
+        };
+
+        let labels: Vec<String> = self
+            .export_candidates(options)
+            .filter(|x| x.rva() != 0)
+            .filter_map(|symbol| {
+                let name = demangle_name(symbol.symbol(), options).ok()?;
+
+                Some(format!(
+                    "{{\"module\":{},\"address\":\"{:#x}\",\"manual\":true,\"text\":{}}}",
+                    json_string(&module),
+                    symbol.rva(),
+                    json_string(&sanitizer.sanitize(&name))
+                ))
+            })
+            .collect();
+
+        format!("{{\"labels\":[{}]}}", labels.join(","))
+    }
+
+    /// produces input for Ghidra's `ImportSymbolsScript.py`: one `name address f|l` line per
+    /// placed symbol, creating a function for the ones link.exe flagged as such and a label
+    /// for the rest
+    pub fn export_ghidra(&self, options: &ExportOptions) -> String {
+        let sanitizer = options.sanitizer.unwrap_or(&GhidraSanitizer);
+        let address_format = options.address_format.unwrap_or(AddressFormat::HexPrefixed);
+
+        let mut output = String::new();
+
+        for symbol in self.export_candidates(options).filter(|x| x.rva() != 0) {
+            let Ok(name) = demangle_name(symbol.symbol(), options) else {
+                continue;
+            };
+
+            output.push_str(&format!(
+                "{} {} {}\n",
+                sanitizer.sanitize(&name),
+                address_format.format(symbol.rva_plus_base(self)),
+                match symbol.flags().contains(SymbolFlags::IS_FUNCTION) {
+                    true => "f",
+                    false => "l",
+                }
+            ));
+        }
+
+        output
+    }
+
+    /// every symbol as a json array of `{"address", "name", "symbol", "kind"}` objects in
+    /// address order, for tools without an exporter of their own. `name` is demangled per
+    /// `options` and only sanitized if `options` asks for it, `symbol` is the name as it
+    /// appears in the map
+    pub fn export_json(&self, options: &ExportOptions) -> String {
+        let sanitizer = options.sanitizer.unwrap_or(&VerbatimSanitizer);
+
+        let symbols: Vec<String> = self
+            .export_candidates(options)
+            .filter_map(|symbol| {
+                let name = demangle_name(symbol.symbol(), options).ok()?;
+
+                Some(format!(
+                    "{{\"address\":{},\"name\":{},\"symbol\":{},\"kind\":\"{}\"}}",
+                    symbol.rva_plus_base(self),
+                    json_string(&sanitizer.sanitize(&name)),
+                    json_string(symbol.symbol()),
+                    match symbol.get().kind {
+                        SymbolKind::Public => "public",
+                        SymbolKind::Static => "static",
+                    }
+                ))
+            })
+            .collect();
+
+        format!("[{}]", symbols.join(","))
+    }
+
+    /// a json array of `{"address", "name", "type"}` objects for Binary Ninja's symbol import,
+    /// with absolute addresses. symbols in CODE sections become a `FunctionSymbol`, all
+    /// others a `DataSymbol`. names are demangled per `options` and only sanitized if
+    /// `options` asks for it
+    pub fn export_binaryninja(&self, options: &ExportOptions) -> String {
+        let sanitizer = options.sanitizer.unwrap_or(&VerbatimSanitizer);
+
+        let symbols: Vec<String> = self
+            .export_candidates(options)
+            .filter(|x| x.rva() != 0)
+            .filter_map(|symbol| {
+                let name = demangle_name(symbol.symbol(), options).ok()?;
+
+                Some(format!(
+                    "{{\"address\":{},\"name\":{},\"type\":\"{}\"}}",
+                    symbol.rva_plus_base(self),
+                    json_string(&sanitizer.sanitize(&name)),
+                    match self.class_of(symbol.addr()) {
+                        Some(Class::Code) => "FunctionSymbol",
+                        _ => "DataSymbol",
+                    }
+                ))
+            })
+            .collect();
+
+        format!("[{}]", symbols.join(","))
+    }
+
+    /// a `{"module", "timestamp", "base", "symbols"}` object for symbolication services, with
+    /// every symbol in a section as `{"rva", "name", "size", "kind"}` in address order.
+    ///
+    /// `timestamp` is the raw link time, the same value a symbol server keys images on. `name`
+    /// is the mangled name, `size` is guessed like [`MapFile::export_ida_functions`] does and
+    /// `kind` is `function` for symbols in CODE sections and `data` for everything else
+    pub fn export_symbol_server_json(&self) -> String {
+        let symbols: Vec<String> = self
+            .inferred_sizes()
+            .into_iter()
+            .map(|(symbol, size)| {
+                format!(
+                    "{{\"rva\":{},\"name\":{},\"size\":{},\"kind\":\"{}\"}}",
+                    symbol.rva(),
+                    json_string(symbol.symbol()),
+                    size,
+                    match self.class_of(symbol.addr()) {
+                        Some(Class::Code) => "function",
+                        _ => "data",
+                    }
+                )
+            })
+            .collect();
+
+        format!(
+            "{{\"module\":{},\"timestamp\":{},\"base\":{},\"symbols\":[{}]}}",
+            json_string(&self.file_name),
+            self.timestamp_raw,
+            self.preferred_load_addr,
+            symbols.join(",")
+        )
+    }
+
+    /// just the dll's public api: one `ordinal name rva` line per export, in ordinal order.
+    /// the rva is `-` for exports that no public backs, like forwarders
+    pub fn export_api(&self) -> String {
+        let mut output = String::new();
+
+        for export in self.export_api_symbols() {
+            let rva = match export.resolve(self) {
+                Some(function) => format!("{:#x}", function.rva.0),
+                None => String::from("-"),
+            };
+
+            output.push_str(&format!(
+                "{} {} {}\n",
+                export.ordinal,
+                export.exported_name(),
+                rva
+            ));
+        }
+
+        output
+    }
+}
+
+#[test]
+fn export_ida() {
+    let map_data = std::fs::read("csgo-x86.map").unwrap();
+    let map_string = String::from_utf8(map_data).unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    std::fs::write("ida.sym", map.export_ida(&Default::default())).unwrap();
+}
+
+#[test]
+fn export_ida_rename() {
+    let map_data = std::fs::read("csgo-x86.map").unwrap();
+    let map_string = String::from_utf8(map_data).unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let mut options = ExportOptions::default();
+    options
+        .renames
+        .insert("_lj_BC_ISLT", "luajit_is_less_than".to_owned());
+
+    let output = map.export_ida(&options);
+
+    assert!(output.contains(" luajit_is_less_than luajit_is_less_than\n"));
+    assert!(!output.contains("_lj_BC_ISLT"));
+
+    // whitespace would split the line into more than `<va> <name> <label>`
+    options
+        .renames
+        .insert("_lj_BC_ISLT", "luajit is\nless than".to_owned());
+
+    let output = map.export_ida(&options);
+
+    assert!(output.contains(" luajit_is_less_than luajit_is_less_than\n"));
+    assert!(output.lines().all(|x| x.split(' ').count() == 3));
+}
+
+#[test]
+fn export_breakpad() {
+    let map_string = std::fs::read_to_string("fixtures/mapinfo-exports.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let output = map.export_breakpad();
+    let mut lines = output.lines();
+
+    assert_eq!(
+        lines.next(),
+        Some("MODULE windows x86 000000000000000000000000000000000 mapinfo-exports.pdb")
+    );
+    assert_eq!(lines.next(), Some("PUBLIC 1000 0 _DllMain@12"));
+    assert_eq!(lines.next(), Some("PUBLIC 1040 0 _CreateInterface@8"));
+    assert!(!output.contains("___safe_se_handler_count"));
+}
+
+#[test]
+fn export_api() {
+    let map_string = std::fs::read_to_string("fixtures/mapinfo-exports.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    assert_eq!(
+        map.export_api(),
+        "1 CreateInterface 0x1040\n2 ?helper@@YAXXZ 0x1100\n"
+    );
+}
+
+#[test]
+fn export_x64dbg() {
+    let map_string = std::fs::read_to_string("fixtures/mapinfo-exports.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let output: serde_json::Value =
+        serde_json::from_str(&map.export_x64dbg(&Default::default())).unwrap();
+    let labels = output["labels"].as_array().unwrap();
+
+    // everything but the absolute symbol, statics included
+    assert_eq!(labels.len(), 6);
+    assert_eq!(labels[0]["module"], "mapinfo-exports.dll");
+    assert_eq!(labels[0]["address"], "0x1000");
+    assert_eq!(labels[0]["text"], "_DllMain@12");
+    assert_eq!(labels[0]["manual"], true);
+    assert!(labels
+        .iter()
+        .any(|x| x["text"] == "_static_helper" && x["address"] == "0x1200"));
+}
+
+#[test]
+fn export_ida_skip_synthetic_sections() {
+    let map_string = std::fs::read_to_string("fixtures/anonymous-comdat.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let output = map.export_ida(&Default::default());
+    assert!(output.contains("_folded_thunk"));
+
+    let options = ExportOptions {
+        skip_synthetic_sections: true,
+        ..Default::default()
+    };
+
+    let output = map.export_ida(&options);
+    assert!(!output.contains("_folded_thunk"));
+    assert!(output.contains("_main"));
+}
+
+#[test]
+fn export_ida_custom_sanitizer() {
+    let map_string = std::fs::read_to_string("fixtures/mapinfo-exports.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let options = ExportOptions {
+        sanitizer: Some(&crate::R2Sanitizer),
+        ..Default::default()
+    };
+
+    let output = map.export_ida(&options);
+    assert!(output.contains(" _CreateInterface_8 _CreateInterface@8\n"));
+
+    // the json exporters keep names as they are unless told otherwise
+    assert!(map
+        .export_json(&Default::default())
+        .contains("\"name\":\"_CreateInterface@8\""));
+    assert!(map
+        .export_json(&options)
+        .contains("\"name\":\"_CreateInterface_8\""));
+
+    let options = ExportOptions {
+        sanitizer: Some(&crate::VerbatimSanitizer),
+        ..Default::default()
+    };
+
+    let output = map.export_ida(&options);
+    assert!(output.contains(" _CreateInterface@8 _CreateInterface@8\n"));
+}
+
+#[test]
+fn export_ida_anonymous_namespace() {
+    let map_string = std::fs::read_to_string("fixtures/anonymous-comdat.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let options = ExportOptions {
+        sanitizer: Some(&crate::GhidraSanitizer),
+        ..Default::default()
+    };
+
+    let output = map.export_ida(&options);
+    assert!(output.contains(" `anonymous_namespace'::helper "));
+
+    let options = ExportOptions {
+        sanitizer: Some(&crate::GhidraSanitizer),
+        anonymous_namespace: Some("(anon)"),
+        ..Default::default()
+    };
+
+    let output = map.export_ida(&options);
+    assert!(output.contains(" (anon)::helper ?helper@?A0x1b2c3d4e@@YAXXZ\n"));
+}
+
+#[test]
+fn export_ida_functions() {
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let output = map.export_ida_functions();
+    let mut lines = output.lines().skip(2);
+
+    assert_eq!(
+        lines.next(),
+        Some("ida_funcs.add_func(0x10001000, 0x10001040)")
+    );
+    // the two folded Release functions share one definition
+    assert_eq!(
+        lines.next(),
+        Some("ida_funcs.add_func(0x10001040, 0x10001060)")
+    );
+    assert_eq!(
+        lines.next(),
+        Some("ida_funcs.add_func(0x10001060, 0x10001080)")
+    );
+    assert_eq!(
+        lines.next(),
+        Some("ida_funcs.add_func(0x10001080, 0x10001100)")
+    );
+    assert_eq!(
+        lines.next(),
+        Some("ida_funcs.add_func(0x10001100, 0x10001200)")
+    );
+    // _g_state is data
+    assert_eq!(lines.next(), None);
+}
+
+#[test]
+fn export_ida_address_format() {
+    let map_string = std::fs::read_to_string("fixtures/mapinfo-exports.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let line = |address_format| {
+        let options = ExportOptions {
+            address_format,
+            ..Default::default()
+        };
+
+        map.export_ida(&options)
+            .lines()
+            .find(|x| x.ends_with(" _DllMain@12"))
+            .unwrap()
+            .split(' ')
+            .next()
+            .unwrap()
+            .to_owned()
+    };
+
+    assert_eq!(line(None), "268439552");
+    assert_eq!(line(Some(AddressFormat::Decimal)), "268439552");
+    assert_eq!(line(Some(AddressFormat::Hex)), "10001000");
+    assert_eq!(line(Some(AddressFormat::HexPrefixed)), "0x10001000");
+}
+
+#[test]
+fn export_ida_demangle_flags() {
+    let map_data = std::fs::read("csgo-x86.map").unwrap();
+    let map_string = String::from_utf8(map_data).unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let name_only = ExportOptions {
+        demangle_flags: Some(DemangleFlags::NAME_ONLY),
+        ..Default::default()
+    };
+    let complete = ExportOptions {
+        demangle_flags: Some(DemangleFlags::COMPLETE),
+        ..Default::default()
+    };
+
+    let output = map.export_ida(&Default::default());
+    assert_eq!(output, map.export_ida(&name_only));
+    assert_ne!(output, map.export_ida(&complete));
+
+    // the label column is the mangled name either way
+    assert_eq!(
+        output.lines().count(),
+        map.export_ida(&complete).lines().count()
+    );
+}
+
+#[test]
+fn demangled_symbols() {
+    let map_string = std::fs::read_to_string("fixtures/mapinfo-exports.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let lenient = map.demangled_symbols(&Default::default());
+    assert_eq!(lenient.len(), 7);
+    assert!(lenient.contains(&(0x10001000, "_DllMain@12".to_owned())));
+    assert!(lenient.contains(&(0x10001100, "helper".to_owned())));
+
+    let never = map.demangled_symbols(&ExportOptions {
+        demangle: DemangleMode::Never,
+        ..Default::default()
+    });
+    assert_eq!(never.len(), 7);
+    assert!(never.contains(&(0x10001100, "?helper@@YAXXZ".to_owned())));
+
+    // plain C names don't demangle, so only the C++ function and the string literal are left
+    let options = ExportOptions {
+        demangle: DemangleMode::Always,
+        ..Default::default()
+    };
+    let always = map.demangled_symbols(&options);
+    assert_eq!(always.len(), 2);
+    assert!(always.contains(&(0x10001100, "helper".to_owned())));
+    assert!(always.contains(&(0x10002000, "`string'".to_owned())));
+
+    let failures = map.demangle_failures(&options);
+    assert_eq!(failures.len(), 5);
+    assert!(failures.iter().any(|(x, _)| x.symbol() == "_DllMain@12"));
+    assert!(map.demangle_failures(&Default::default()).is_empty());
+}
+
+#[test]
+fn export_ida_dedup() {
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let output = map.export_ida(&Default::default());
+    assert_eq!(output.lines().count(), 7);
+    assert_eq!(output.matches("268439616 ").count(), 2);
+
+    let first = map.export_ida(&ExportOptions {
+        dedup: DedupPolicy::First,
+        ..Default::default()
+    });
+    assert_eq!(first.lines().count(), 6);
+    assert!(first.contains("?Release@Bar@@UAEXXZ"));
+    assert!(!first.contains("?Release@Foo@@UAEXXZ"));
+
+    let mut options = ExportOptions {
+        dedup: DedupPolicy::LongestName,
+        ..Default::default()
+    };
+    options
+        .renames
+        .insert("?Release@Bar@@UAEXXZ", "Bar::ReleaseAll".to_owned());
+
+    let longest = map.export_ida(&options);
+    assert_eq!(longest.lines().count(), 6);
+    assert!(longest.contains(" Bar__ReleaseAll Bar__ReleaseAll\n"));
+    assert!(!longest.contains("?Release@Foo@@UAEXXZ"));
+}
+
+#[test]
+fn export_ghidra() {
+    let map_string = std::fs::read_to_string("fixtures/mapinfo-exports.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let output = map.export_ghidra(&Default::default());
+    let lines: Vec<&str> = output.lines().collect();
+
+    // everything but the absolute symbol
+    assert_eq!(lines.len(), 6);
+    assert_eq!(lines[0], "_DllMain@12 0x10001000 f");
+    assert!(lines.contains(&"helper 0x10001100 f"));
+    assert!(lines.contains(&"_g_counter 0x10003000 l"));
+}
+
+#[test]
+fn export_json() {
+    let map_string = std::fs::read_to_string("fixtures/mapinfo-exports.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let output: serde_json::Value =
+        serde_json::from_str(&map.export_json(&Default::default())).unwrap();
+    let symbols = output.as_array().unwrap();
+
+    assert_eq!(symbols.len(), 7);
+    assert_eq!(symbols[1]["address"], 0x10001000);
+    assert_eq!(symbols[1]["name"], "_DllMain@12");
+    assert_eq!(symbols[1]["kind"], "public");
+    assert!(symbols
+        .iter()
+        .any(|x| x["name"] == "helper" && x["symbol"] == "?helper@@YAXXZ"));
+    assert!(symbols
+        .iter()
+        .any(|x| x["name"] == "_static_helper" && x["kind"] == "static"));
+}
+
+#[test]
+fn export_rva_range() {
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let options = ExportOptions {
+        rva_range: Some(0x1040..0x1080),
+        ..Default::default()
+    };
+
+    let output = map.export_ida(&options);
+    assert_eq!(output.lines().count(), 3);
+    assert!(output.contains("_local_helper"));
+    assert!(!output.contains("_init"));
+    assert!(!output.contains("_shutdown"));
+
+    assert_eq!(map.export_ghidra(&options).lines().count(), 3);
+    assert_eq!(map.demangled_symbols(&options).len(), 3);
+}
+
+#[test]
+fn export_binaryninja() {
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let output = map.export_binaryninja(&Default::default());
+    let symbols: Vec<serde_json::Value> = serde_json::from_str(&output).unwrap();
+
+    assert_eq!(
+        symbols.len(),
+        map.symbols().filter(|x| x.rva() != 0).count()
+    );
+    assert!(symbols.iter().any(|x| x["name"] == "_init"
+        && x["address"] == 0x10001000u64
+        && x["type"] == "FunctionSymbol"));
+    assert!(symbols.iter().any(|x| x["type"] == "DataSymbol"));
+}
+
+#[test]
+fn export_symbol_server_json() {
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let output: serde_json::Value = serde_json::from_str(&map.export_symbol_server_json()).unwrap();
+
+    assert_eq!(output["module"], "aliases");
+    assert_eq!(output["timestamp"], 0x63e17200);
+    assert_eq!(output["base"], 0x10000000);
+
+    let symbols = output["symbols"].as_array().unwrap();
+    assert_eq!(symbols.len(), 7);
+    assert_eq!(symbols[0]["rva"], 0x1000);
+    assert_eq!(symbols[0]["name"], "_init");
+    assert_eq!(symbols[0]["size"], 0x40);
+    assert_eq!(symbols[0]["kind"], "function");
+
+    let g_state = symbols.iter().find(|x| x["name"] == "_g_state").unwrap();
+    assert_eq!(g_state["rva"], 0x3010);
+    assert_eq!(g_state["kind"], "data");
+}
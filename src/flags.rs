@@ -0,0 +1,62 @@
+use bitflags::bitflags;
+
+#[cfg(test)]
+use crate::{LibObject, MapFile};
+
+bitflags! {
+    /// the single character flags link.exe puts between the rva and the lib:object columns
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct SymbolFlags: u8 {
+        /// `f`, the symbol is a function
+        const IS_FUNCTION = 1 << 0;
+        /// `i`, the function was defined inline (a header member function or `inline` function
+        /// the compiler emitted out of line). it has nothing to do with imports
+        const IS_INLINE = 1 << 1;
+    }
+}
+
+impl SymbolFlags {
+    /// the flag a column token stands for. other single character tokens in front of the
+    /// lib:object end up in [`Symbol::unknown_flags`](crate::Symbol::unknown_flags)
+    pub fn from_token(token: &str) -> Option<Self> {
+        match token {
+            "f" => Some(SymbolFlags::IS_FUNCTION),
+            "i" => Some(SymbolFlags::IS_INLINE),
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn symbol_flags() {
+    let map_data = std::fs::read("csgo-x86.map").unwrap();
+    let map_string = String::from_utf8(map_data).unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let get = map
+        .functions
+        .iter()
+        .find(|x| x.symbol == "?Get@ClientEntityList@sdk@csgo@aw@@SAPAV1234@XZ")
+        .unwrap();
+    assert_eq!(get.flags, SymbolFlags::IS_FUNCTION | SymbolFlags::IS_INLINE);
+    assert!(get.unknown_flags.is_empty());
+
+    let unknown = map_string.replace(
+        "1000cdb0 f i aimbot_base.obj",
+        "1000cdb0 f x aimbot_base.obj",
+    );
+    let map = MapFile::load(&unknown).unwrap();
+
+    let get = map
+        .functions
+        .iter()
+        .find(|x| x.symbol == "?Get@ClientEntityList@sdk@csgo@aw@@SAPAV1234@XZ")
+        .unwrap();
+    assert!(get.flags.contains(SymbolFlags::IS_FUNCTION));
+    assert!(!get.flags.contains(SymbolFlags::IS_INLINE));
+    // not a flag we know, but it is kept apart from the lib:object
+    assert_eq!(get.unknown_flags, ['x']);
+    assert!(matches!(&get.libobj, LibObject::LibObj(None, obj) if obj == "aimbot_base.obj"));
+}
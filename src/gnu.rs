@@ -0,0 +1,357 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use anyhow::{Context, Result};
+
+use crate::{
+    Address, Class, EncodingInfo, Function, LibObject, MapFile, MapParseError, PublicsLayout, Rva,
+    Section, SymbolFlags, SymbolKind,
+};
+
+//
+// GNU ld (`-Map`) writes something like this. only the part after
+// `Linker script and memory map` is of interest:
+//
+// Linker script and memory map
+//
+// LOAD <object>
+//                 <va>                . = SEGMENT_START ("text-segment", <load_address>)
+//
+// <output_section> <va> <size>
+//  <input_section>  <va> <size> <object or archive(member)>
+//                 <va>                <symbol>
+//  <very_long_input_section>
+//                   <va> <size> <object>
+//
+// OUTPUT(<file_name> <bfd_target>)
+//
+
+/// sections that aren't loaded into memory and only add noise
+fn is_non_alloc(name: &str) -> bool {
+    name.starts_with(".debug")
+        || name.starts_with(".stab")
+        || name.starts_with(".gnu.attributes")
+        || name.starts_with(".note.GNU-stack")
+        || name == ".comment"
+        || name == "/DISCARD/"
+}
+
+pub(crate) fn class_of_section(name: &str) -> Class<'static> {
+    match [".text", ".init", ".fini", ".plt"]
+        .iter()
+        .any(|x| name.starts_with(x))
+    {
+        true => Class::Code,
+        false => Class::Data,
+    }
+}
+
+fn parse_hex(token: &str) -> Result<usize> {
+    usize::from_str_radix(token.trim_start_matches("0x"), 16).context("unable to parse address")
+}
+
+/// splits off the first `n` whitespace separated columns, handing back the rest of the line as is
+fn columns(data: &str, n: usize) -> (Vec<&str>, &str) {
+    let mut rest = data.trim_start();
+    let mut columns = Vec::with_capacity(n);
+
+    while columns.len() < n && !rest.is_empty() {
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        columns.push(&rest[..end]);
+        rest = rest[end..].trim_start();
+    }
+
+    (columns, rest.trim_end())
+}
+
+/// `libfoo.a(bar.o)` or just `bar.o`
+pub(crate) fn parse_libobj(file: &str) -> LibObject<'_> {
+    match file.strip_suffix(')').and_then(|x| x.split_once('(')) {
+        Some((lib, obj)) => LibObject::LibObj(Some(lib.into()), obj.into()),
+        None => LibObject::LibObj(None, file.into()),
+    }
+}
+
+/// a section name that was too long to share a line with its address and size
+enum Wrapped<'a> {
+    Output(&'a str),
+    Input,
+}
+
+/// the output section being walked, if it is one that gets loaded
+struct CurrentSection {
+    seg: u32,
+    va: usize,
+    class: Class<'static>,
+}
+
+#[derive(Default)]
+struct GnuParser<'a> {
+    file_name: Option<&'a str>,
+    load_address: Option<usize>,
+    sections: Vec<Section<'a>>,
+    functions: Vec<Function<'a>>,
+    section: Option<CurrentSection>,
+    object: Option<&'a str>,
+    wrapped: Option<Wrapped<'a>>,
+    // editor line number of the current line
+    line: usize,
+}
+
+impl<'a> GnuParser<'a> {
+    fn output_section(&mut self, name: &'a str, va: &str, size: &str) -> Result<()> {
+        self.object = None;
+
+        if is_non_alloc(name) {
+            self.section = None;
+            return Ok(());
+        }
+
+        let class = class_of_section(name);
+        let seg = self.sections.len() as u32 + 1;
+
+        self.sections.push(Section {
+            name: name.into(),
+            class: class.clone(),
+            addr: Address { seg, addr: 0 },
+            len: parse_hex(size).context("unable to parse length")?,
+        });
+
+        self.section = Some(CurrentSection {
+            seg,
+            va: parse_hex(va)?,
+            class,
+        });
+
+        Ok(())
+    }
+
+    fn symbol(&mut self, va: &str, name: &'a str) -> Result<()> {
+        // assignments like `_edata = .` or `PROVIDE (...)`, the text segment start is the
+        // closest thing ld has to a preferred load address
+        if name.contains('=') {
+            if self.load_address.is_none()
+                && (name.contains("\"text-segment\"") || name.contains("__image_base__"))
+            {
+                self.load_address = parse_hex(va).ok();
+            }
+
+            return Ok(());
+        }
+
+        let (Some(section), Some(object)) = (&self.section, self.object) else {
+            return Ok(());
+        };
+
+        let va = parse_hex(va)?;
+        let load_address = self.load_address.unwrap_or_default();
+
+        self.functions.push(Function {
+            kind: SymbolKind::Public,
+            symbol: name.into(),
+            addr: Address {
+                seg: section.seg,
+                addr: va
+                    .checked_sub(section.va)
+                    .context("symbol is in front of its section")?,
+            },
+            rva: Rva(va
+                .checked_sub(load_address)
+                .context("symbol is in front of the load address")?),
+            flags: match section.class {
+                Class::Code => SymbolFlags::IS_FUNCTION,
+                _ => SymbolFlags::empty(),
+            },
+            unknown_flags: vec![],
+            libobj: parse_libobj(object),
+            source_line: self.line,
+        });
+
+        Ok(())
+    }
+
+    fn parse_line(&mut self, data: &'a str) -> Result<()> {
+        if let Some(output) = data.strip_prefix("OUTPUT(") {
+            self.file_name = output.split(' ').next();
+            return Ok(());
+        }
+
+        if data.trim().is_empty() || data.starts_with("LOAD ") {
+            return Ok(());
+        }
+
+        if let Some(wrapped) = self.wrapped.take() {
+            let (columns, object) = columns(data, 2);
+            let [va, size] = columns[..] else {
+                anyhow::bail!("no address and size were found after the section name");
+            };
+
+            match wrapped {
+                Wrapped::Output(name) => self.output_section(name, va, size)?,
+                Wrapped::Input => self.object = Some(object).filter(|x| !x.is_empty()),
+            }
+
+            return Ok(());
+        }
+
+        match data.strip_prefix(' ') {
+            // an output section
+            None => {
+                let (columns, _) = columns(data, 3);
+
+                match columns[..] {
+                    [name] => self.wrapped = Some(Wrapped::Output(name)),
+                    [name, va, size] => self.output_section(name, va, size)?,
+                    // linker script statements at the top level
+                    _ => {}
+                }
+            }
+            // a symbol or an assignment
+            Some(rest) if rest.starts_with(' ') => {
+                let (columns, name) = columns(data, 1);
+
+                if columns[0].starts_with("0x") && !name.is_empty() {
+                    self.symbol(columns[0], name)?;
+                }
+            }
+            // input section patterns like `*(.text)` and padding
+            Some(rest) if rest.starts_with('*') => {}
+            // an input section, naming the object the following symbols come from
+            Some(_) => {
+                let (columns, object) = columns(data, 3);
+
+                match columns[..] {
+                    [_] => self.wrapped = Some(Wrapped::Input),
+                    [_, _, _] => self.object = Some(object).filter(|x| !x.is_empty()),
+                    _ => self.object = None,
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn build(mut self) -> MapFile<'a> {
+        // ld lists symbols by address within an input section, but input sections
+        // aren't necessarily in order
+        self.functions.sort_by_key(|x| x.rva.0);
+
+        MapFile {
+            file_name: self.file_name.unwrap_or_default().into(),
+            entrypoint: None,
+            preferred_load_addr: self.load_address.unwrap_or_default(),
+            timestamp: "".into(),
+            timestamp_raw: 0,
+            sections: self.sections,
+            functions: self.functions,
+            static_symbols: vec![],
+            exports: vec![],
+            publics_layout: PublicsLayout::default(),
+            encoding: EncodingInfo::default(),
+        }
+    }
+}
+
+/// parses a map written by GNU ld's `-Map`. every symbol ends up in `functions`, as ld doesn't
+/// tell statics apart, and there is no timestamp or entry point
+pub(crate) fn parse(input: &str) -> Result<MapFile<'_>, MapParseError> {
+    let start = input
+        .lines()
+        .position(|x| x.starts_with("Linker script and memory map"))
+        .context("no `Linker script and memory map` line found")
+        .map_err(|error| MapParseError {
+            line: 1,
+            byte_offset: 0,
+            len: 0,
+            error,
+        })?;
+
+    let mut parser = GnuParser::default();
+
+    for (line, data) in input.lines().enumerate().skip(start + 1) {
+        parser.line = line + 1;
+        parser.parse_line(data).map_err(|error| MapParseError {
+            line: line + 1,
+            byte_offset: data.as_ptr() as usize - input.as_ptr() as usize,
+            len: data.len(),
+            error,
+        })?;
+    }
+
+    Ok(parser.build())
+}
+
+#[test]
+fn parse_gnu_ld() {
+    let map_string = std::fs::read_to_string("fixtures/gnu-ld.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    assert_eq!(map.file_name, "a.out");
+    assert_eq!(map.preferred_load_addr, 0x400000);
+
+    let sections: Vec<&str> = map.sections.iter().map(|x| &*x.name).collect();
+    assert_eq!(sections, [".text", ".rodata", ".data"]);
+    assert_eq!(map.sections[0].class, Class::Code);
+    assert_eq!(map.sections[0].len, 0x120);
+    assert_eq!(map.sections[2].class, Class::Data);
+
+    let symbols: Vec<&str> = map.functions.iter().map(|x| &*x.symbol).collect();
+    assert_eq!(
+        symbols,
+        [
+            "_start",
+            "main",
+            "helper",
+            "long_named",
+            "printf",
+            "g_counter"
+        ]
+    );
+
+    let helper = &map.functions[2];
+    assert_eq!(helper.rva.0, 0x1050);
+    assert_eq!(helper.addr, Address { seg: 1, addr: 0x50 });
+    assert_eq!(helper.flags, SymbolFlags::IS_FUNCTION);
+    assert!(matches!(&helper.libobj, LibObject::LibObj(None, obj) if obj == "main.o"));
+
+    let long_named = &map.functions[3];
+    assert!(matches!(&long_named.libobj, LibObject::LibObj(None, obj) if obj == "main.o"));
+
+    let printf = &map.functions[4];
+    assert!(matches!(
+        &printf.libobj,
+        LibObject::LibObj(Some(lib), obj) if lib == "/usr/lib/libc.a" && obj == "printf.o"
+    ));
+
+    let g_counter = &map.functions[5];
+    assert_eq!(map.class_of(&g_counter.addr), Some(Class::Data));
+    assert!(g_counter.flags.is_empty());
+}
+
+#[test]
+fn parse_gnu_ld_forced_format() {
+    let map_string = std::fs::read_to_string("fixtures/gnu-ld.map").unwrap();
+
+    let error = MapFile::load_with(
+        &map_string,
+        &crate::ParseOptions::new().format(crate::MapFormat::Msvc),
+    )
+    .err()
+    .unwrap();
+    assert!(error.to_string().starts_with("no map header found"));
+
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+
+    let error = MapFile::load_with(
+        &map_string,
+        &crate::ParseOptions::new().format(crate::MapFormat::GnuLd),
+    )
+    .err()
+    .unwrap();
+    assert!(matches!(
+        &error,
+        crate::ParseError::Other(error)
+            if error.error.to_string() == "no `Linker script and memory map` line found"
+    ));
+}
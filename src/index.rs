@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+
+use crate::{MapFile, SymbolRef};
+
+/// publics and static symbols sorted by rva once, for answering lots of address lookups in
+/// O(log n) each, e.g. when symbolicating stack traces.
+///
+/// symbols with a zero rva (absolute or never placed) have no address and are left out
+pub struct SymbolIndex<'m> {
+    map: &'m MapFile<'m>,
+    symbols: Vec<SymbolRef<'m>>,
+}
+
+impl<'m> SymbolIndex<'m> {
+    /// the symbol starting at or closest below `rva`. of several symbols sharing an rva,
+    /// publics come first, in map order
+    pub fn nearest_below(&self, rva: usize) -> Option<SymbolRef<'m>> {
+        let idx = self.symbols.partition_point(|x| x.rva() <= rva);
+        let symbol = self.symbols[..idx].last()?;
+
+        let first = self.symbols[..idx]
+            .iter()
+            .rposition(|x| x.rva() != symbol.rva())
+            .map_or(0, |x| x + 1);
+
+        Some(self.symbols[first])
+    }
+
+    /// the symbol covering `rva`, taking each one to run up to the next symbol or the end of
+    /// its section like [`MapFile::symbol_at`] does
+    pub fn symbol_at(&self, rva: usize) -> Option<SymbolRef<'m>> {
+        let symbol = self.nearest_below(rva)?;
+
+        let section = self.map.section_containing(symbol.addr())?;
+        let section_end = symbol.rva() + (section.end() - symbol.addr().addr);
+
+        (rva < section_end).then_some(symbol)
+    }
+
+    /// whether any symbol covers `rva`, see [`SymbolIndex::symbol_at`]
+    pub fn contains(&self, rva: usize) -> bool {
+        self.symbol_at(rva).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+}
+
+impl<'a> MapFile<'a> {
+    /// sorts every placed symbol by rva for repeated lookups, see [`SymbolIndex`]
+    pub fn index(&self) -> SymbolIndex<'_> {
+        let mut symbols: Vec<SymbolRef> = self.symbols().filter(|x| x.rva() != 0).collect();
+        // stable, so publics stay ahead of statics and aliases keep their map order
+        symbols.sort_by_key(|x| x.rva());
+
+        SymbolIndex { map: self, symbols }
+    }
+
+    /// mangled name -> symbol, for resolving lots of names. where names repeat, the one
+    /// [`MapFile::symbol_named`] would find wins
+    pub fn name_index(&self) -> HashMap<&str, SymbolRef<'_>> {
+        let mut index = HashMap::with_capacity(self.functions.len() + self.static_symbols.len());
+
+        for symbol in self.symbols() {
+            index.entry(symbol.symbol()).or_insert(symbol);
+        }
+
+        index
+    }
+}
+
+#[test]
+fn index_matches_linear_scan() {
+    let map_data = std::fs::read("csgo-x86.map").unwrap();
+    let map_string = String::from_utf8(map_data).unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+    let index = map.index();
+
+    let placed: Vec<SymbolRef> = map.symbols().filter(|x| x.rva() != 0).collect();
+    assert_eq!(index.len(), placed.len());
+
+    for symbol in placed.iter().step_by(37) {
+        for rva in [symbol.rva() - 1, symbol.rva(), symbol.rva() + 1] {
+            let linear = placed
+                .iter()
+                .filter(|x| x.rva() <= rva)
+                .map(|x| x.rva())
+                .max();
+
+            assert_eq!(index.nearest_below(rva).map(|x| x.rva()), linear);
+        }
+    }
+
+    assert!(index.nearest_below(0).is_none());
+}
+
+#[test]
+fn index_symbol_at() {
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+    let index = map.index();
+
+    assert!(!index.contains(0xfff));
+    assert_eq!(index.symbol_at(0x1000).unwrap().symbol(), "_init");
+    assert_eq!(
+        index.nearest_below(0x1050).unwrap().symbol(),
+        "?Release@Foo@@UAEXXZ"
+    );
+    assert_eq!(index.symbol_at(0x1064).unwrap().symbol(), "_local_helper");
+    assert!(index.contains(0x11ff));
+    assert!(!index.contains(0x1200));
+}
+
+#[test]
+fn name_index() {
+    let map_data = std::fs::read("csgo-x86.map").unwrap();
+    let map_string = String::from_utf8(map_data).unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+    let index = map.name_index();
+
+    for symbol in map.symbols().step_by(41) {
+        let found = index[symbol.symbol()];
+        let expected = map.symbol_named(symbol.symbol()).unwrap();
+
+        assert!(std::ptr::eq(found.get(), expected.get()));
+    }
+}
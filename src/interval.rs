@@ -0,0 +1,127 @@
+use alloc::vec::Vec;
+
+use crate::{MapFile, SymbolRef};
+
+struct Node<'m> {
+    start: usize,
+    end: usize,
+    /// highest `end` in the subtree rooted here
+    max_end: usize,
+    symbol: SymbolRef<'m>,
+}
+
+/// every symbol's inferred `[rva, rva + size)` range, arranged for containment queries.
+///
+/// a binary search over rva-sorted symbols only hands back the closest symbol below an address.
+/// when ranges overlap (aliases, folded functions) and you need *all* symbols covering an
+/// address, or you're doing millions of containment checks, build this once and query it
+/// instead. building it costs a sort and an extra allocation per symbol
+pub struct IntervalTree<'m> {
+    // sorted by start; the tree is implicit, with each subrange's midpoint as its root
+    nodes: Vec<Node<'m>>,
+}
+
+impl<'m> IntervalTree<'m> {
+    fn new(mut nodes: Vec<Node<'m>>) -> Self {
+        nodes.sort_by_key(|x| (x.start, x.end));
+
+        fn augment(nodes: &mut [Node]) -> usize {
+            if nodes.is_empty() {
+                return 0;
+            }
+
+            let mid = nodes.len() / 2;
+            let left = augment(&mut nodes[..mid]);
+            let right = augment(&mut nodes[mid + 1..]);
+
+            let node = &mut nodes[mid];
+            node.max_end = node.end.max(left).max(right);
+            node.max_end
+        }
+
+        augment(&mut nodes);
+
+        IntervalTree { nodes }
+    }
+
+    /// every symbol whose range contains `rva`, in ascending address order
+    pub fn query(&self, rva: usize) -> Vec<SymbolRef<'m>> {
+        fn visit<'m>(nodes: &[Node<'m>], rva: usize, out: &mut Vec<SymbolRef<'m>>) {
+            if nodes.is_empty() {
+                return;
+            }
+
+            let mid = nodes.len() / 2;
+            let node = &nodes[mid];
+
+            if node.max_end <= rva {
+                return;
+            }
+
+            visit(&nodes[..mid], rva, out);
+
+            if node.start <= rva {
+                if rva < node.end {
+                    out.push(node.symbol);
+                }
+
+                visit(&nodes[mid + 1..], rva, out);
+            }
+        }
+
+        let mut out = Vec::new();
+        visit(&self.nodes, rva, &mut out);
+        out
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+impl<'a> MapFile<'a> {
+    /// builds an [`IntervalTree`] of `rva..rva + size` ranges. maps carry no sizes, so each
+    /// symbol runs up to the next higher symbol or its section's end, and ones outside any
+    /// section are left out
+    pub fn to_interval_tree(&self) -> IntervalTree<'_> {
+        IntervalTree::new(
+            self.inferred_sizes()
+                .into_iter()
+                .map(|(symbol, size)| Node {
+                    start: symbol.rva(),
+                    end: symbol.rva() + size,
+                    max_end: 0,
+                    symbol,
+                })
+                .collect(),
+        )
+    }
+}
+
+#[test]
+fn interval_tree_query() {
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+    let tree = map.to_interval_tree();
+
+    assert_eq!(tree.len(), map.functions.len() + map.static_symbols.len());
+
+    let names = |rva| -> Vec<&str> { tree.query(rva).iter().map(|x| x.symbol()).collect() };
+
+    // both folded Release functions cover the same range
+    assert_eq!(
+        names(0x1050),
+        ["?Release@Foo@@UAEXXZ", "?Release@Bar@@UAEXXZ"]
+    );
+    assert_eq!(names(0x1060), ["_local_helper"]);
+    assert_eq!(names(0x1000), ["_init"]);
+    // _shutdown stops at the end of .text$mn rather than running into .text$x
+    assert_eq!(names(0x10ff), ["_shutdown"]);
+    assert_eq!(names(0x1100), ["_unwind"]);
+    assert!(names(0x1200).is_empty());
+}
@@ -1,8 +1,79 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
 /// mapparse .map parser for the aimware self-leak
 ///
 /// made by unknowntrojan#2814
-use anyhow::{Context, Result};
-use msvc_demangler::{CallingConv, StorageClass, Type};
+extern crate alloc;
+
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use alloc::{format, vec};
+
+use anyhow::{Context, Error, Result};
+
+#[cfg(feature = "bloom")]
+mod bloom;
+#[cfg(feature = "std")]
+mod diff;
+mod error;
+#[cfg(feature = "std")]
+mod export;
+mod flags;
+mod gnu;
+#[cfg(feature = "std")]
+mod index;
+#[cfg(feature = "interval-tree")]
+mod interval;
+mod lld;
+mod owned;
+pub mod prelude;
+#[cfg(feature = "python")]
+mod python;
+mod query;
+#[cfg(feature = "std")]
+mod reader;
+#[cfg(feature = "std")]
+mod sanitize;
+#[cfg(feature = "std")]
+mod search;
+#[cfg(feature = "std")]
+mod summary;
+mod symbol;
+#[cfg(feature = "std")]
+mod validate;
+#[cfg(feature = "wasm")]
+mod wasm;
+
+#[cfg(feature = "bloom")]
+pub use bloom::NameBloom;
+#[cfg(feature = "std")]
+pub use diff::MapDiff;
+use error::Failure;
+pub use error::ParseError;
+#[cfg(feature = "std")]
+pub use export::{AddressFormat, DedupPolicy, DemangleMode, ExportOptions};
+pub use flags::SymbolFlags;
+#[cfg(feature = "std")]
+pub use index::SymbolIndex;
+#[cfg(feature = "interval-tree")]
+pub use interval::IntervalTree;
+#[cfg(feature = "std")]
+pub use msvc_demangler::DemangleFlags;
+pub use owned::{EncodingInfo, MapFileOwned, TextEncoding};
+pub use query::DEFAULT_THUNK_PREFIXES;
+#[cfg(feature = "std")]
+pub use sanitize::{
+    GhidraSanitizer, IdaSanitizer, NameSanitizer, R2Sanitizer, VerbatimSanitizer, X64dbgSanitizer,
+};
+#[cfg(feature = "std")]
+pub use search::{SearchOptions, SymbolSearch};
+#[cfg(feature = "std")]
+pub use summary::MapSummary;
+pub use symbol::{MergeByRva, SymbolRef};
+#[cfg(feature = "std")]
+pub use validate::ValidationIssue;
+#[cfg(feature = "wasm")]
+pub use wasm::parse_map;
 
 //
 // This particular map file is composed like this:
@@ -26,443 +97,954 @@ use msvc_demangler::{CallingConv, StorageClass, Type};
 // Static symbols
 //
 // <seg>:<addr>		<symbol>	<rva>	<flags>	<obj>
+//
+// Exports (only with /MAPINFO:EXPORTS)
+//
+// ordinal	name
+//
+// <ordinal>	<symbol>
+//				exported name: <name>
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rva(pub usize);
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Address {
+    pub seg: u32,
+    pub addr: usize,
+}
 
-struct Rva(usize);
-
-struct Address {
-    seg: u16,
-    addr: usize,
+impl Address {
+    /// segment 0 is where link.exe puts `<absolute>` symbols. their `addr` is the symbol's value,
+    /// like `___guard_flags`, rather than a place in the image
+    pub fn is_absolute(&self) -> bool {
+        self.seg == 0
+    }
 }
 
-#[derive(Debug)]
-enum Class {
+/// the class column of the section table
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Class<'a> {
     Code,
     Data,
+    /// zero-initialized data
+    Bss,
+    /// read-only data
+    Const,
+    /// thread local storage
+    Tls,
+    Stack,
+    /// the row had no class column, only produced by lenient parsing
+    Unknown,
+    /// any other class, as written in the map
+    Other(Cow<'a, str>),
 }
 
-struct Section<'a> {
-    name: &'a str,
-    class: Class,
-    addr: Address,
-    len: usize,
+/// link.exe names some contributions it synthesizes itself, like `/* anonymous */` for an
+/// unnamed COMDAT, and other linkers use `<anonymous>`
+fn is_synthetic_name(name: &str) -> bool {
+    (name.starts_with("/*") && name.ends_with("*/"))
+        || (name.starts_with('<') && name.ends_with('>'))
 }
 
-#[derive(Debug)]
-enum LibObject<'a> {
-    LibObj(Option<&'a str>, &'a str),
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Section<'a> {
+    pub name: Cow<'a, str>,
+    pub class: Class<'a>,
+    pub addr: Address,
+    pub len: usize,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LibObject<'a> {
+    LibObj(Option<Cow<'a, str>>, Cow<'a, str>),
     Absolute,
 }
 
-struct Function<'a> {
-    pub symbol: &'a str,
-    pub addr: Address,
-    pub rva: Rva,
-    pub flags: Vec<&'a str>,
-    pub libobj: LibObject<'a>,
+/// which table a symbol was listed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SymbolKind {
+    /// `Publics by Value`
+    Public,
+    /// `Static symbols`
+    Static,
 }
 
-struct StaticSymbol<'a> {
-    pub symbol: &'a str,
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Symbol<'a> {
+    pub kind: SymbolKind,
+    pub symbol: Cow<'a, str>,
     pub addr: Address,
     pub rva: Rva,
-    pub flags: Vec<&'a str>,
+    pub flags: SymbolFlags,
+    /// single character tokens between the rva and the lib:object that aren't a known
+    /// [`SymbolFlags`] flag, in the order they appear
+    pub unknown_flags: Vec<char>,
     pub libobj: LibObject<'a>,
+    /// line of the map this symbol's row is on, counting from 1 like editors do
+    pub source_line: usize,
+}
+
+/// a symbol from [`MapFile::functions`], kept as a name for the public table's rows
+pub type Function<'a> = Symbol<'a>;
+
+/// a symbol from [`MapFile::static_symbols`], kept as a name for the static table's rows
+pub type StaticSymbol<'a> = Symbol<'a>;
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Export<'a> {
+    pub ordinal: u16,
+    /// the decorated public symbol backing this export
+    pub symbol: Cow<'a, str>,
+    /// the name the export is published under, if it differs from `symbol`
+    pub name: Option<Cow<'a, str>>,
+}
+
+impl<'a> Section<'a> {
+    /// whether this section's name was made up by the linker rather than coming from the object
+    pub fn is_synthetic(&self) -> bool {
+        is_synthetic_name(&self.name)
+    }
+
+    /// offset one past the last byte of the section, in the same segment as `addr`
+    pub fn end(&self) -> usize {
+        self.addr.addr + self.len
+    }
+
+    /// whether `addr` falls into `[addr, end())`. empty sections contain nothing
+    pub fn contains(&self, addr: &Address) -> bool {
+        self.addr.seg == addr.seg && addr.addr >= self.addr.addr && addr.addr < self.end()
+    }
+
+    /// where the section starts relative to the image base. `None` if the segment's base isn't
+    /// known, see [`MapFile::segment_rva`]
+    pub fn rva(&self, map: &MapFile) -> Option<usize> {
+        Some(map.segment_rva(self.addr.seg)? + self.addr.addr)
+    }
+}
+
+impl<'a> Symbol<'a> {
+    /// the address this symbol ends up at when the image is loaded at its preferred base,
+    /// as shown in the `Rva+Base` column. use this instead of adding the load address yourself.
+    /// maps without a load address, like lld-link's `/lldmap`, give the rva
+    pub fn rva_plus_base(&self, map: &MapFile) -> usize {
+        // a zero rva is the image header, which no symbol lives in. the parser produces it for
+        // absolute symbols with a zero value and for symbols the linker never placed, neither
+        // of which is relative to the load address
+        match self.rva.0 {
+            0 => 0,
+            rva => rva + map.preferred_load_addr,
+        }
+    }
+
+    /// the absolute virtual address, i.e. [`Symbol::rva_plus_base`]. symbols with a zero rva
+    /// stay at 0 instead of landing on the load address
+    pub fn va(&self, map: &MapFile) -> usize {
+        self.rva_plus_base(map)
+    }
 }
 
-struct MapFile<'a> {
-    pub file_name: &'a str,
-    pub entrypoint: Address,
+impl<'a> Export<'a> {
+    /// the name a consumer of the dll would import this by
+    pub fn exported_name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.symbol)
+    }
+
+    /// finds the public that backs this export
+    pub fn resolve<'m>(&self, map: &'m MapFile<'a>) -> Option<&'m Function<'a>> {
+        map.functions.iter().find(|x| x.symbol == self.symbol)
+    }
+}
+
+/// how the address columns of the publics and static symbol tables are laid out
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PublicsLayout {
+    /// a single `Rva+Base` column holding the preferred virtual address
+    #[default]
+    RvaPlusBase,
+    /// separate `Rva` and `Base` columns
+    SplitRvaBase,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MapFile<'a> {
+    /// empty for lld-link `/lldmap` maps, which don't name the module
+    pub file_name: Cow<'a, str>,
+    /// `None` for minimal maps that have no `entry point at` line. those also have no
+    /// separate static symbols table, so every symbol ends up in `functions` and
+    /// `static_symbols` stays empty
+    pub entrypoint: Option<Address>,
+    /// 0 for lld-link `/lldmap` maps, which don't record it, so vas from those are rvas
     pub preferred_load_addr: usize,
-    pub timestamp: &'a str,
+    /// the link time as written out by the linker, e.g. `Mon Feb  6 22:32:48 2023`
+    pub timestamp: Cow<'a, str>,
+    /// the link time in seconds since the unix epoch, as stored in the pe header
+    pub timestamp_raw: u32,
     pub sections: Vec<Section<'a>>,
     pub functions: Vec<Function<'a>>,
     pub static_symbols: Vec<StaticSymbol<'a>>,
+    pub exports: Vec<Export<'a>>,
+    pub publics_layout: PublicsLayout,
+    /// only filled in by [`MapFileOwned::load_bytes`], maps parsed from text keep the default
+    pub encoding: EncodingInfo,
+}
+
+/// the lines in front of the section table, see [`MapFile::parse_header`]
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MapHeader<'a> {
+    pub file_name: Cow<'a, str>,
+    pub preferred_load_addr: usize,
+    pub timestamp: Cow<'a, str>,
+    pub timestamp_raw: u32,
+}
+
+/// where and why parsing a map failed, for failures [`ParseError`] has no variant of its own
+#[derive(Debug)]
+pub struct MapParseError {
+    /// 1-based line number
+    pub line: usize,
+    /// offset of the offending token from the start of the input, for pointing an editor at it
+    pub byte_offset: usize,
+    /// length of the offending token
+    pub len: usize,
+    pub error: Error,
+}
+
+impl core::fmt::Display for MapParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        // `{:#}` prints the whole context chain, like it does for the anyhow error itself
+        match f.alternate() {
+            true => write!(f, "{:#} (line {})", self.error, self.line),
+            false => write!(f, "{} (line {})", self.error, self.line),
+        }
+    }
+}
+
+impl core::error::Error for MapParseError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        Some(self.error.as_ref())
+    }
+}
+
+/// knobs for [`MapFile::load_with`]. the default is what [`MapFile::load`] does
+///
+/// ```
+/// use mapparse::{MapFile, ParseOptions, Radix};
+///
+/// let options = ParseOptions::new().lenient(true).rva_radix(Radix::Hex);
+///
+/// let input = std::fs::read_to_string("csgo-x86.map").unwrap();
+/// let map = MapFile::load_with(&input, &options).unwrap();
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ParseOptions {
+    /// make do with rows that are missing trailing columns instead of failing the whole parse,
+    /// e.g. a section row without a class gets [`Class::Unknown`]
+    pub lenient: bool,
+    /// how the rva column of the symbol tables is written
+    pub rva_radix: Radix,
+    /// which linker wrote the map, `None` to tell by looking at it
+    pub format: Option<MapFormat>,
+}
+
+/// the linker a map comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapFormat {
+    /// `link.exe /MAP`
+    Msvc,
+    /// GNU ld's `-Map`
+    GnuLd,
+    /// lld-link's `/lldmap`. its `/map` output is link.exe's format.
+    ///
+    /// these maps have no module name, timestamp, entry point or image base, so
+    /// [`MapFile::preferred_load_addr`] is 0 and every va computed from them is just the rva
+    Lld,
+}
+
+impl MapFormat {
+    /// GNU ld maps always have a `Linker script and memory map` line and lld-link maps start
+    /// with an `Address Size Align Out In Symbol` line. anything else is taken to be from
+    /// link.exe
+    pub fn detect(input: &str) -> Self {
+        if input
+            .lines()
+            .find(|x| !x.trim().is_empty())
+            .is_some_and(lld::is_header)
+        {
+            return MapFormat::Lld;
+        }
+
+        match input
+            .lines()
+            .any(|x| x.starts_with("Linker script and memory map"))
+        {
+            true => MapFormat::GnuLd,
+            false => MapFormat::Msvc,
+        }
+    }
+}
+
+/// number base of a column
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Radix {
+    /// what link.exe writes
+    #[default]
+    Hex,
+    /// what some map converters write. values marked as hex with `0x` or a trailing `h` are
+    /// still read as hex
+    Decimal,
+}
+
+impl ParseOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// see [`ParseOptions::lenient`](#structfield.lenient)
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
+    /// see [`ParseOptions::rva_radix`](#structfield.rva_radix)
+    pub fn rva_radix(mut self, rva_radix: Radix) -> Self {
+        self.rva_radix = rva_radix;
+        self
+    }
+
+    /// see [`ParseOptions::format`](#structfield.format)
+    pub fn format(mut self, format: MapFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+}
+
+impl Radix {
+    fn parse(self, token: &str) -> core::result::Result<usize, core::num::ParseIntError> {
+        if let Some(hex) = token
+            .strip_prefix("0x")
+            .or_else(|| token.strip_suffix(['h', 'H']))
+        {
+            return usize::from_str_radix(hex, 16);
+        }
+
+        match self {
+            Radix::Hex => usize::from_str_radix(token, 16),
+            Radix::Decimal => token.parse(),
+        }
+    }
 }
 
 impl<'a> MapFile<'a> {
-    fn load(input: &'a str) -> Result<Self> {
-        #[derive(Debug)]
-        enum Stage {
-            Header,
-            Sections,
-            Functions,
-            StaticSymbols,
+    /// parses a map produced by `link.exe /MAP`, GNU ld's `-Map` or lld-link's `/lldmap`, see
+    /// [`MapFormat::detect`]. lld-link maps don't record the image base, see [`MapFormat::Lld`]
+    pub fn load(input: &'a str) -> Result<Self, ParseError> {
+        Self::load_with(input, &ParseOptions::default())
+    }
+
+    pub fn load_with(input: &'a str, options: &ParseOptions) -> Result<Self, ParseError> {
+        let map = match options.format.unwrap_or_else(|| MapFormat::detect(input)) {
+            MapFormat::GnuLd => gnu::parse(input),
+            MapFormat::Lld => lld::parse(input),
+            MapFormat::Msvc => {
+                let mut parser = Parser {
+                    options: *options,
+                    ..Default::default()
+                };
+
+                parser.parse(input).and_then(|()| parser.finish(input))
+            }
+        };
+
+        map.map_err(|error| ParseError::new(error, input))
+    }
+
+    /// reads only up to the section table, for indexing lots of maps by module, link time
+    /// and load address without parsing their symbols. link.exe maps only
+    pub fn parse_header(input: &'a str) -> Result<MapHeader<'a>, ParseError> {
+        let mut parser = Parser::default();
+
+        parser
+            .parse_until(input, |x| !matches!(x, Stage::Header), Err)
+            .and_then(|()| {
+                parser.build_header().map_err(|error| MapParseError {
+                    line: input.lines().count() + 1,
+                    byte_offset: input.len(),
+                    len: 0,
+                    error,
+                })
+            })
+            .map_err(|error| ParseError::new(error, input))
+    }
+
+    /// like [`MapFile::parse`], but a line that fails to parse is skipped and reported instead
+    /// of ending the parse, e.g. for a map cut off mid-download. the map is `None` only if the
+    /// header is incomplete. GNU ld and lld maps are still parsed all-or-nothing
+    pub fn parse_lenient(input: &'a str) -> (Option<Self>, Vec<ParseError>) {
+        if MapFormat::detect(input) != MapFormat::Msvc {
+            return match Self::parse(input) {
+                Ok(map) => (Some(map), vec![]),
+                Err(error) => (None, vec![error]),
+            };
         }
 
-        let mut stage = Stage::Header;
+        let mut parser = Parser::default();
+        let mut errors = Vec::new();
 
-        let mut filename: Option<&str> = None;
-        let mut timestamp: Option<&str> = None;
-        let mut load_address: Option<usize> = None;
-        let mut entry_point: Option<Address> = None;
-        let mut sections: Vec<Section> = Default::default();
-        let mut functions: Vec<Function> = Default::default();
-        let mut static_symbols: Vec<StaticSymbol> = Default::default();
+        let result = parser.parse_until(
+            input,
+            |_| false,
+            |error| {
+                errors.push(error);
+                Ok(())
+            },
+        );
 
-        for (line, data) in input.split("\r\n").enumerate() {
-            // we are using zero-based indices, but i would like to use editor line numbers
-            // using line numbers in general is yucky, but there is for example no clean way for me
-            // to know which line the filename line is, as it does not contain anything else
-            let line = line + 1;
-
-            match stage {
-                Stage::Header => match line {
-                    1 => filename = Some(data.trim()),
-                    3 => {
-                        let begin = data.find('(').context("there was no timestamp on line 3")?;
-                        let end = data.find(')').context("there was no timestamp on line 3")?;
-
-                        timestamp = Some(&data[begin + 1..end - 1])
-                    }
-                    5 => {
-                        load_address = Some(
-                            usize::from_str_radix(
-                                &data[data.find("is ").context(
-                                    "there was no preferred load address statement on line 5",
-                                )? + 3..],
-                                16,
-                            )
-                            .context("unable to get preferred load address from line 5")?,
-                        )
-                    }
-                    7 => stage = Stage::Sections,
-                    _ => {}
-                },
-                Stage::Sections => {
-                    if data.contains("Publics by Value") {
-                        stage = Stage::Functions;
-                        continue;
-                    }
+        // a fatal error already explains why the map can't be finished
+        let map = match result {
+            Ok(()) => parser
+                .finish(input)
+                .map_err(|error| errors.push(error))
+                .ok(),
+            Err(error) => {
+                errors.push(error);
+                parser.finish(input).ok()
+            }
+        };
 
-                    // hacky way to know we are on an actual data line
-                    if !data.contains('0') {
-                        continue;
-                    }
+        let errors = errors
+            .into_iter()
+            .map(|error| ParseError::new(error, input))
+            .collect();
 
-                    enum SectionStage {
-                        Address,
-                        Length,
-                        Symbol,
-                        Class,
-                    }
+        (map, errors)
+    }
 
-                    let mut section_stage = SectionStage::Address;
+    /// parses as much as possible, handing back whatever was accumulated up to the first
+    /// fatal error alongside that error. the map is `None` if the error hit before the
+    /// header was complete
+    pub fn load_partial(input: &'a str) -> (Option<Self>, Option<ParseError>) {
+        let mut parser = Parser::default();
+
+        let (map, error) = match parser.parse(input) {
+            Ok(()) => match parser.finish(input) {
+                Ok(map) => (Some(map), None),
+                Err(error) => (None, Some(error)),
+            },
+            Err(error) => (parser.finish(input).ok(), Some(error)),
+        };
+
+        (map, error.map(|error| ParseError::new(error, input)))
+    }
+}
 
-                    let mut address: Option<Address> = None;
-                    let mut length: Option<usize> = None;
-                    let mut symbol: Option<&str> = None;
-                    let mut class: Option<Class> = None;
+#[derive(Debug, Default)]
+enum Stage {
+    #[default]
+    Header,
+    Sections,
+    Functions,
+    StaticSymbols,
+    Exports,
+}
 
-                    for substring in data.split(' ') {
-                        if substring.is_empty() {
-                            continue;
-                        }
+/// how many lines in front of the `Timestamp is` line the module name may be: the module
+/// name, a blank line, then the timestamp
+const MODULE_NAME_DISTANCE: usize = 4;
 
-                        match section_stage {
-                            SectionStage::Address => {
-                                let addrstr: Vec<&str> = substring.split(':').collect();
+/// index of the module name line: the last non-empty line before the first `Timestamp is` line
+fn find_map_start(input: &str) -> Result<usize> {
+    let lines: Vec<&str> = input.lines().collect();
 
-                                // these will panic if the format is invalid
-                                let seg = addrstr[0];
-                                let addr = addrstr[1];
+    let timestamp = lines
+        .iter()
+        .position(|x| x.trim_start().starts_with("Timestamp is"))
+        .context("no map header found, expected a module name followed by a `Timestamp is` line")?;
 
-                                address = Some(Address {
-                                    seg: seg.parse().context("unable to parse segment")?,
-                                    addr: usize::from_str_radix(addr, 16)
-                                        .context("unable to parse address")?,
-                                });
+    (timestamp.saturating_sub(MODULE_NAME_DISTANCE)..timestamp)
+        .rev()
+        .find(|&idx| !lines[idx].trim().is_empty())
+        .context("no module name found in front of the `Timestamp is` line")
+}
 
-                                section_stage = SectionStage::Length;
-                            }
-                            SectionStage::Length => {
-                                length = Some(
-                                    usize::from_str_radix(&substring[0..substring.len() - 1], 16)
-                                        .context("unable to parse length")?,
-                                );
+/// parses a `<seg>:<addr>` token
+fn parse_address(token: &str) -> Result<Address> {
+    let (seg, addr) = token
+        .split_once(':')
+        .context(Failure::BadAddress("address is not in `<seg>:<addr>` form"))?;
+
+    Ok(Address {
+        seg: seg
+            .parse()
+            .context(Failure::BadAddress("unable to parse segment"))?,
+        addr: usize::from_str_radix(addr, 16)
+            .context(Failure::BadAddress("unable to parse address"))?,
+    })
+}
 
-                                section_stage = SectionStage::Symbol;
-                            }
-                            SectionStage::Symbol => {
-                                symbol = Some(substring);
+/// splits a lib:object column at the first colon, leaving any further ones to the object.
+/// a colon right after a leading drive letter (`C:\`) is part of a path, not the separator
+fn split_libobj(libobj: &str) -> (Option<&str>, &str) {
+    let is_drive = |idx: usize| {
+        idx == 1
+            && libobj.starts_with(|x: char| x.is_ascii_alphabetic())
+            && libobj[idx + 1..].starts_with(['\\', '/'])
+    };
+
+    match libobj.match_indices(':').find(|&(idx, _)| !is_drive(idx)) {
+        Some((idx, _)) => (Some(&libobj[..idx]), &libobj[idx + 1..]),
+        None => (None, libobj),
+    }
+}
 
-                                section_stage = SectionStage::Class;
-                            }
-                            SectionStage::Class => {
-                                class = Some(match substring {
-                                    "CODE" => Class::Code,
-                                    "DATA" => Class::Data,
-                                    _ => {
-                                        panic!("unrecognized section class {}", substring);
-                                    }
-                                });
-                            }
-                        }
-                    }
+/// whether `data` is a table row, i.e. starts with the segment number of a `<seg>:<addr>`
+/// token. column headers, blank lines and table titles never start with a digit, while
+/// rows with a mangled address still count so they get reported instead of skipped
+fn is_data_row(data: &str) -> bool {
+    data.trim_start().starts_with(|x: char| x.is_ascii_digit())
+}
 
-                    sections.push(Section {
-                        addr: address.context("no address was found")?,
-                        len: length.context("no length was found")?,
-                        name: symbol.context("no symbol was found")?,
-                        class: class.context("no class was found")?,
-                    })
-                }
-                Stage::Functions => {
-                    if data.contains("entry point at") {
-                        stage = Stage::StaticSymbols;
+/// the state threaded through `MapFile::load` while walking the map line by line
+#[derive(Default)]
+struct Parser<'a> {
+    options: ParseOptions,
+    stage: Stage,
+    filename: Option<Cow<'a, str>>,
+    timestamp: Option<Cow<'a, str>>,
+    timestamp_raw: Option<u32>,
+    load_address: Option<usize>,
+    entry_point: Option<Address>,
+    sections: Vec<Section<'a>>,
+    functions: Vec<Function<'a>>,
+    static_symbols: Vec<StaticSymbol<'a>>,
+    exports: Vec<Export<'a>>,
+    publics_layout: PublicsLayout,
+    // lines in front of the module name
+    header_start: usize,
+    // offset into the current line and length of the token being looked at, for error reporting
+    token: (usize, usize),
+    // editor line number of the current line
+    line: usize,
+}
 
-                        for substring in data.split(' ') {
-                            if substring.is_empty() {
-                                continue;
-                            }
+impl<'a> Parser<'a> {
+    fn parse(&mut self, input: &'a str) -> Result<(), MapParseError> {
+        self.parse_until(input, |_| false, Err)
+    }
 
-                            if substring.contains('0') {
-                                let addrstr: Vec<&str> = substring.split(':').collect();
+    /// walks the lines until `done` says the stage reached is far enough. a line that fails
+    /// to parse goes to `on_error`, which decides whether to carry on with the next one
+    fn parse_until(
+        &mut self,
+        input: &'a str,
+        done: impl Fn(&Stage) -> bool,
+        mut on_error: impl FnMut(MapParseError) -> Result<(), MapParseError>,
+    ) -> Result<(), MapParseError> {
+        // archived maps sometimes have the linker's command line or log glued to the front,
+        // so treat everything before the module name as noise
+        self.header_start = find_map_start(input).map_err(|error| MapParseError {
+            line: 1,
+            byte_offset: 0,
+            len: 0,
+            error,
+        })?;
+
+        for (line, data) in input.lines().enumerate().skip(self.header_start) {
+            if done(&self.stage) {
+                break;
+            }
 
-                                // these will panic if the format is invalid
-                                let seg = addrstr[0];
-                                let addr = addrstr[1];
+            // a utf-8 bom isn't whitespace, so trimming would leave it on the module name
+            let data = match line {
+                0 => data.strip_prefix('\u{feff}').unwrap_or(data),
+                _ => data,
+            };
 
-                                entry_point = Some(Address {
-                                    seg: seg.parse().context("unable to parse segment")?,
-                                    addr: usize::from_str_radix(addr, 16)
-                                        .context("unable to parse address")?,
-                                });
-                            }
-                        }
+            self.token = (0, data.len());
+            self.line = line + 1;
 
-                        continue;
-                    }
+            // we are using zero-based indices, but i would like to use editor line numbers
+            if let Err(error) = self.parse_line(data) {
+                // lines() hands out subslices of the input, so this is where the line starts
+                let line_start = data.as_ptr() as usize - input.as_ptr() as usize;
+
+                on_error(MapParseError {
+                    line: line + 1,
+                    byte_offset: line_start + self.token.0,
+                    len: self.token.1,
+                    error,
+                })?;
+            }
+        }
 
-                    // hacky way to know we are on an actual data line
-                    if !data.contains('0') {
-                        continue;
-                    }
+        Ok(())
+    }
+
+    fn parse_line(&mut self, data: &'a str) -> Result<()> {
+        // blank lines separate the tables, and trail them in some maps. none carry anything
+        if data.trim().is_empty() {
+            return Ok(());
+        }
 
-                    enum FunctionStage {
-                        Address,
-                        Symbol,
-                        Rva,
-                        LibObj,
+        match self.stage {
+            // linkers differ in how many blank lines they put between these, so go by content.
+            // everything in front of the module name was already skipped
+            Stage::Header => {
+                let trimmed = data.trim();
+
+                if self.filename.is_none() {
+                    self.filename = Some(trimmed.into());
+                } else if let Some(rest) = trimmed.strip_prefix("Timestamp is ") {
+                    let (raw, readable) = rest
+                        .split_once('(')
+                        .context("there was no human readable timestamp")?;
+                    let readable = readable
+                        .strip_suffix(')')
+                        .context("the human readable timestamp is not closed")?;
+
+                    self.timestamp_raw = Some(
+                        u32::from_str_radix(raw.trim(), 16).context("unable to parse timestamp")?,
+                    );
+                    self.timestamp = Some(readable.into());
+                } else if let Some(rest) = trimmed.strip_prefix("Preferred load address is ") {
+                    self.load_address = Some(
+                        usize::from_str_radix(rest, 16)
+                            .context("unable to get preferred load address")?,
+                    );
+                } else if trimmed.starts_with("Start") && trimmed.contains("Length") {
+                    // every rva is computed from the load address, so fail here rather than on
+                    // the first symbol
+                    self.load_address
+                        .context(Failure::MissingHeaderField("preferred load address"))?;
+
+                    self.stage = Stage::Sections;
+                }
+            }
+            Stage::Sections => {
+                if data.contains("Publics by Value") {
+                    if !data.contains("Rva+Base") && data.contains("Base") {
+                        self.publics_layout = PublicsLayout::SplitRvaBase;
                     }
 
-                    let mut function_stage = FunctionStage::Address;
-                    let mut address: Option<Address> = None;
-                    let mut symbol: Option<&str> = None;
-                    let mut rva: Option<Rva> = None;
-                    let mut flags: Vec<&str> = Default::default();
-                    let mut libobj: Option<LibObject> = None;
+                    self.stage = Stage::Functions;
+                    return Ok(());
+                }
 
-                    for substring in data.split(' ') {
-                        if substring.is_empty() {
-                            continue;
-                        }
+                if !is_data_row(data) {
+                    return Ok(());
+                }
 
-                        match function_stage {
-                            FunctionStage::Address => {
-                                let addrstr: Vec<&str> = substring.split(':').collect();
+                // the name column may hold a synthetic name with spaces in it, like
+                // `/* anonymous */`, so peel the fixed columns off both ends of the row
+                // and keep whatever is in between as the name
+                let row = data.trim();
+                let (addrstr, row) = row
+                    .split_once(char::is_whitespace)
+                    .context("no length was found")?;
+                let (lenstr, row) = row
+                    .trim_start()
+                    .split_once(char::is_whitespace)
+                    .context("no symbol was found")?;
+                let (name, classstr) = match row.trim().rsplit_once(char::is_whitespace) {
+                    Some((name, classstr)) => (name, Some(classstr)),
+                    // a truncated row, keep the section and let the class be unknown
+                    None if self.options.lenient => (row.trim(), None),
+                    None => return Err(Failure::SectionClass(None).into()),
+                };
+
+                // the row was trimmed, so the address doesn't start the line
+                let start = addrstr.as_ptr() as usize - data.as_ptr() as usize;
+
+                self.token = (start, addrstr.len());
+                let address = parse_address(addrstr)?;
+
+                let length = usize::from_str_radix(lenstr.strip_suffix('H').unwrap_or(lenstr), 16)
+                    .context("unable to parse length")?;
+
+                let class = match classstr {
+                    Some("CODE") => Class::Code,
+                    Some("DATA") => Class::Data,
+                    Some("BSS") => Class::Bss,
+                    Some("CONST") => Class::Const,
+                    Some("TLS") => Class::Tls,
+                    Some("STACK") => Class::Stack,
+                    Some(classstr) => Class::Other(classstr.into()),
+                    None => Class::Unknown,
+                };
+
+                self.sections.push(Section {
+                    addr: address,
+                    len: length,
+                    name: name.trim_end().into(),
+                    class,
+                })
+            }
+            Stage::Functions => {
+                if let Some(entry_point) = data.trim().strip_prefix("entry point at") {
+                    self.stage = Stage::StaticSymbols;
+                    self.entry_point = Some(parse_address(entry_point.trim())?);
 
-                                // these will panic if the format is invalid
-                                let seg = addrstr[0];
-                                let addr = addrstr[1];
+                    return Ok(());
+                }
 
-                                address = Some(Address {
-                                    seg: seg.parse().context("unable to parse segment")?,
-                                    addr: usize::from_str_radix(addr, 16)
-                                        .context("unable to parse address")?,
-                                });
+                if !is_data_row(data) {
+                    return Ok(());
+                }
 
-                                function_stage = FunctionStage::Symbol;
-                            }
-                            FunctionStage::Symbol => {
-                                symbol = Some(substring);
-                                function_stage = FunctionStage::Rva
-                            }
-                            FunctionStage::Rva => {
-                                let rva_with_base = usize::from_str_radix(substring, 16)
-                                    .context("unable to parse rva")?;
-
-                                let val = if rva_with_base == 0 {
-                                    0
-                                } else {
-                                    rva_with_base - load_address.unwrap()
-                                };
-
-                                rva = Some(Rva(val));
-                                function_stage = FunctionStage::LibObj;
-                            }
-                            FunctionStage::LibObj => {
-                                match substring.contains("<absolute>") {
-                                    true => libobj = Some(LibObject::Absolute),
-                                    false => {
-                                        // this is code responsible for both LibObj and flags cases.
-                                        // this is a bit retarded, but we can't have a flag state,
-                                        // as we would need to switch match cases which isn't possible
-                                        // as we don't have goto.
-                                        match substring.len() {
-                                            1 => {
-                                                // FLAG!
-                                                flags.push(substring)
-                                            }
-                                            _ => {
-                                                let libobjstr: Vec<&str> =
-                                                    substring.split(':').collect();
-
-                                                match libobjstr.len() {
-                                                    1 => {
-                                                        libobj = Some(LibObject::LibObj(
-                                                            None,
-                                                            libobjstr[0],
-                                                        ))
-                                                    }
-                                                    _ => {
-                                                        libobj = Some(LibObject::LibObj(
-                                                            Some(libobjstr[0]),
-                                                            libobjstr[1],
-                                                        ))
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
+                let symbol = self.parse_symbol(data, SymbolKind::Public)?;
+                self.functions.push(symbol);
+            }
+            Stage::StaticSymbols => {
+                if data.trim() == "Exports" {
+                    self.stage = Stage::Exports;
+                    return Ok(());
+                }
 
-                    functions.push(Function {
-                        addr: address.context("no address was found")?,
-                        symbol: symbol.context("no symbol was found")?,
-                        rva: rva.context("no rva was found")?,
-                        flags,
-                        libobj: libobj.context("no libobj was found")?,
-                    })
+                if !is_data_row(data) {
+                    return Ok(());
                 }
-                Stage::StaticSymbols => {
-                    // reused code from function stage
 
-                    // hacky way to know we are on an actual data line
-                    if !data.contains('0') {
-                        continue;
-                    }
+                let symbol = self.parse_symbol(data, SymbolKind::Static)?;
+                self.static_symbols.push(symbol);
+            }
+            Stage::Exports => {
+                let data = data.trim();
+
+                if let Some(name) = data.strip_prefix("exported name:") {
+                    self.exports
+                        .last_mut()
+                        .context("exported name without a preceding export")?
+                        .name = Some(name.trim().into());
+                    return Ok(());
+                }
 
-                    enum FunctionStage {
-                        Address,
-                        Symbol,
-                        Rva,
-                        LibObj,
-                    }
+                let mut parts = data.split_whitespace();
 
-                    let mut function_stage = FunctionStage::Address;
-                    let mut address: Option<Address> = None;
-                    let mut symbol: Option<&str> = None;
-                    let mut rva: Option<Rva> = None;
-                    let mut flags: Vec<&str> = Default::default();
-                    let mut libobj: Option<LibObject> = None;
+                // skips the "ordinal name" column header
+                let ordinal = match parts.next().map(|x| x.parse::<u16>()) {
+                    Some(Ok(ordinal)) => ordinal,
+                    _ => return Ok(()),
+                };
 
-                    for substring in data.split(' ') {
-                        if substring.is_empty() {
-                            continue;
-                        }
+                self.exports.push(Export {
+                    ordinal,
+                    symbol: parts.next().context("no export symbol was found")?.into(),
+                    name: None,
+                })
+            }
+        }
 
-                        match function_stage {
-                            FunctionStage::Address => {
-                                let addrstr: Vec<&str> = substring.split(':').collect();
+        Ok(())
+    }
 
-                                // these will panic if the format is invalid
-                                let seg = addrstr[0];
-                                let addr = addrstr[1];
+    /// one row of the publics or static symbols table
+    fn parse_symbol(&mut self, data: &'a str, kind: SymbolKind) -> Result<Symbol<'a>> {
+        enum FunctionStage {
+            Address,
+            Symbol,
+            Rva,
+            Base,
+            LibObj,
+        }
 
-                                address = Some(Address {
-                                    seg: seg.parse().context("unable to parse segment")?,
-                                    addr: usize::from_str_radix(addr, 16)
-                                        .context("unable to parse address")?,
-                                });
+        let mut function_stage = FunctionStage::Address;
+        let mut address: Option<Address> = None;
+        let mut symbol: Option<&str> = None;
+        let mut rva: Option<Rva> = None;
+        let mut flags = SymbolFlags::empty();
+        let mut unknown_flags = Vec::new();
+        let mut libobj: Option<LibObject> = None;
 
-                                function_stage = FunctionStage::Symbol;
-                            }
-                            FunctionStage::Symbol => {
-                                symbol = Some(substring);
-                                function_stage = FunctionStage::Rva
-                            }
-                            FunctionStage::Rva => {
-                                let rva_with_base = usize::from_str_radix(substring, 16)
-                                    .context("unable to parse rva")?;
-
-                                let val = if rva_with_base == 0 {
-                                    0
-                                } else {
-                                    rva_with_base - load_address.unwrap()
-                                };
-
-                                rva = Some(Rva(val));
-                                function_stage = FunctionStage::LibObj;
-                            }
-                            FunctionStage::LibObj => {
-                                match substring.contains("<absolute>") {
-                                    true => libobj = Some(LibObject::Absolute),
-                                    false => {
-                                        // this is code responsible for both LibObj and flags cases.
-                                        // this is a bit retarded, but we can't have a flag state,
-                                        // as we would need to switch match cases which isn't possible
-                                        // as we don't have goto.
-                                        match substring.len() {
-                                            1 => {
-                                                // FLAG!
-                                                flags.push(substring)
-                                            }
-                                            _ => {
-                                                if substring.len() < 3 {
-                                                    dbg!(substring.len());
-                                                }
-
-                                                let libobjstr: Vec<&str> =
-                                                    substring.split(':').collect();
-
-                                                match libobjstr.len() {
-                                                    1 => {
-                                                        libobj = Some(LibObject::LibObj(
-                                                            None,
-                                                            libobjstr[0],
-                                                        ))
-                                                    }
-                                                    _ => {
-                                                        libobj = Some(LibObject::LibObj(
-                                                            Some(libobjstr[0]),
-                                                            libobjstr[1],
-                                                        ))
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
+        // the columns are padded with runs of spaces (and tabs in hand-edited maps), so any
+        // amount of whitespace separates them. only the lib:object column may contain spaces
+        for substring in data.split_whitespace() {
+            // byte offset of the token, as the lib:object column runs to the end of the line
+            let start = substring.as_ptr() as usize - data.as_ptr() as usize;
+
+            self.token = (start, substring.len());
+
+            match function_stage {
+                FunctionStage::Address => {
+                    address = Some(parse_address(substring)?);
+
+                    function_stage = FunctionStage::Symbol;
+                }
+                FunctionStage::Symbol => {
+                    symbol = Some(substring);
+                    function_stage = FunctionStage::Rva
+                }
+                FunctionStage::Rva => match self.publics_layout {
+                    PublicsLayout::RvaPlusBase => {
+                        let rva_with_base = self
+                            .options
+                            .rva_radix
+                            .parse(substring)
+                            .context(Failure::BadAddress("unable to parse rva"))?;
+
+                        // absolute symbols hold a value rather than an address, so there is
+                        // nothing to take the load address off of. the value is in the address
+                        let val = if rva_with_base == 0
+                            || address.as_ref().is_some_and(Address::is_absolute)
+                        {
+                            0
+                        } else {
+                            let load_address = self
+                                .load_address
+                                .context(Failure::MissingHeaderField("preferred load address"))?;
+
+                            rva_with_base.checked_sub(load_address).with_context(|| {
+                                format!(
+                                    "rva+base {:x} is below the preferred load address {:x}",
+                                    rva_with_base, load_address
+                                )
+                            })?
+                        };
+
+                        rva = Some(Rva(val));
+                        function_stage = FunctionStage::LibObj;
+                    }
+                    PublicsLayout::SplitRvaBase => {
+                        rva = Some(Rva(self
+                            .options
+                            .rva_radix
+                            .parse(substring)
+                            .context(Failure::BadAddress("unable to parse rva"))?));
+                        function_stage = FunctionStage::Base;
+                    }
+                },
+                FunctionStage::Base => {
+                    let base = self
+                        .options
+                        .rva_radix
+                        .parse(substring)
+                        .context("unable to parse base")?;
+
+                    // absolute symbols and ones the linker never placed have no base, like they
+                    // have a zero rva+base in the combined layout. every other row must have
+                    // been placed relative to the preferred load address, or its rva is wrong
+                    if base == 0 || address.as_ref().is_some_and(Address::is_absolute) {
+                        rva = Some(Rva(0));
+                    } else {
+                        let load_address = self
+                            .load_address
+                            .context(Failure::MissingHeaderField("preferred load address"))?;
+
+                        anyhow::ensure!(
+                            base == load_address,
+                            "base {:x} doesn't match the preferred load address {:x}",
+                            base,
+                            load_address
+                        );
+                    }
+
+                    function_stage = FunctionStage::LibObj;
+                }
+                FunctionStage::LibObj => {
+                    match substring.contains("<absolute>") {
+                        true => libobj = Some(LibObject::Absolute),
+                        false => {
+                            // flags and the lib:object share this state: any number of the
+                            // flag tokens link.exe writes, then the lib:object ends the row.
+                            // a single character we don't know is kept as an unknown flag as
+                            // long as something follows it, as a lib:object never is that short
+                            let rest = data[start + substring.len()..].trim();
+
+                            match SymbolFlags::from_token(substring) {
+                                Some(flag) => flags.insert(flag),
+                                None if substring.chars().count() == 1 && !rest.is_empty() => {
+                                    unknown_flags.extend(substring.chars());
+                                }
+                                None => {
+                                    // object paths may contain spaces, so
+                                    // take everything up to the end of the line
+                                    let substring = data[start..].trim_end();
+                                    self.token = (start, substring.len());
+
+                                    let (lib, obj) = split_libobj(substring);
+                                    libobj =
+                                        Some(LibObject::LibObj(lib.map(Into::into), obj.into()));
+
+                                    break;
                                 }
                             }
                         }
                     }
-
-                    static_symbols.push(StaticSymbol {
-                        addr: address.context("no address was found")?,
-                        symbol: symbol.context("no symbol was found")?,
-                        rva: rva.context("no rva was found")?,
-                        flags,
-                        libobj: libobj.context("no libobj was found")?,
-                    })
                 }
             }
         }
 
+        Ok(Symbol {
+            kind,
+            addr: address.context("no address was found")?,
+            symbol: symbol.context("no symbol was found")?.into(),
+            rva: rva.context("no rva was found")?,
+            flags,
+            unknown_flags,
+            libobj: libobj.context("no libobj was found")?,
+            source_line: self.line,
+        })
+    }
+
+    /// errors here are about missing pieces, so they point at the end of the input
+    fn finish(self, input: &str) -> Result<MapFile<'a>, MapParseError> {
+        self.build().map_err(|error| MapParseError {
+            line: input.lines().count() + 1,
+            byte_offset: input.len(),
+            len: 0,
+            error,
+        })
+    }
+
+    fn build_header(&mut self) -> Result<MapHeader<'a>> {
+        Ok(MapHeader {
+            file_name: self
+                .filename
+                .take()
+                .context(Failure::MissingHeaderField("filename"))?,
+            preferred_load_addr: self
+                .load_address
+                .context(Failure::MissingHeaderField("preferred load address"))?,
+            timestamp: self
+                .timestamp
+                .take()
+                .context(Failure::MissingHeaderField("timestamp"))?,
+            timestamp_raw: self
+                .timestamp_raw
+                .context(Failure::MissingHeaderField("timestamp"))?,
+        })
+    }
+
+    fn build(mut self) -> Result<MapFile<'a>> {
+        let header = self.build_header()?;
+
         Ok(MapFile {
-            file_name: filename.context("filename not found")?,
-            entrypoint: entry_point.context("entrypoint not found")?,
-            preferred_load_addr: load_address.context("preferred load address not found")?,
-            timestamp: timestamp.context("timestamp not found")?,
-            sections,
-            functions,
-            static_symbols,
+            file_name: header.file_name,
+            entrypoint: self.entry_point,
+            preferred_load_addr: header.preferred_load_addr,
+            timestamp: header.timestamp,
+            timestamp_raw: header.timestamp_raw,
+            sections: self.sections,
+            functions: self.functions,
+            static_symbols: self.static_symbols,
+            exports: self.exports,
+            publics_layout: self.publics_layout,
+            encoding: EncodingInfo::default(),
         })
     }
 }
 
+#[cfg(feature = "std")]
 #[test]
 fn parse() {
     let map_data = std::fs::read("csgo-x86.map").unwrap();
@@ -470,7 +1052,9 @@ fn parse() {
 
     let map = MapFile::load(&map_string).unwrap();
 
-    println!("Dumping map for object file {}, entry point ({}:{:#04X}), preferred load addr {:#04X}, built on {}", map.file_name, map.entrypoint.seg, map.entrypoint.addr, map.preferred_load_addr, map.timestamp);
+    let entrypoint = map.entrypoint.as_ref().unwrap();
+
+    println!("Dumping map for object file {}, entry point ({}:{:#04X}), preferred load addr {:#04X}, built on {}", map.file_name, entrypoint.seg, entrypoint.addr, map.preferred_load_addr, map.timestamp);
 
     for section in &map.sections {
         println!(
@@ -500,73 +1084,634 @@ fn parse() {
             symbol.addr.addr,
             symbol.flags,
             symbol.libobj,
-            &msvc_demangler::demangle(symbol.symbol, msvc_demangler::DemangleFlags::COMPLETE)
-                .unwrap_or(symbol.symbol.to_owned())
+            &msvc_demangler::demangle(&symbol.symbol, msvc_demangler::DemangleFlags::COMPLETE)
+                .unwrap_or(symbol.symbol.to_string())
         )
     }
 }
 
 #[test]
-fn export_ida() {
-    fn fix_name_for_ida(name: &str) -> String {
-        name.chars()
-            .map(|x| {
-                match "_$?@0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxy"
-                    .contains(x)
-                {
-                    true => x,
-                    false => '_',
-                }
-            })
-            .collect()
-    }
+fn parse_mapinfo_exports() {
+    let map_string = std::fs::read_to_string("fixtures/mapinfo-exports.map").unwrap();
 
-    fn fix_mangled_symbol(sym: &str) -> String {
-        sym.chars()
-            .map(|x| match "<>".contains(x) {
-                true => '_',
-                false => x,
-            })
-            .collect()
+    let map = MapFile::load(&map_string).unwrap();
+
+    assert_eq!(map.functions.len(), 6);
+    assert_eq!(map.static_symbols.len(), 1);
+    assert_eq!(map.exports.len(), 2);
+    assert_eq!(map.timestamp, "Mon Feb  6 22:32:48 2023");
+    assert_eq!(map.timestamp_raw, 0x63e17200);
+    assert_eq!(map.functions[1].kind, SymbolKind::Public);
+    assert_eq!(map.static_symbols[0].kind, SymbolKind::Static);
+
+    let create_interface = &map.exports[0];
+    assert_eq!(create_interface.ordinal, 1);
+    assert_eq!(create_interface.symbol, "_CreateInterface@8");
+    assert_eq!(create_interface.exported_name(), "CreateInterface");
+
+    let function = create_interface.resolve(&map).unwrap();
+    assert_eq!(function.rva.0, 0x1040);
+    assert!(matches!(
+        &function.libobj,
+        LibObject::LibObj(None, obj) if obj == "interface.obj"
+    ));
+
+    let helper = &map.exports[1];
+    assert_eq!(helper.ordinal, 2);
+    assert_eq!(helper.exported_name(), "?helper@@YAXXZ");
+    assert_eq!(helper.resolve(&map).unwrap().rva.0, 0x1100);
+}
+
+#[test]
+fn parse_leading_blank_line() {
+    let map_string = std::fs::read_to_string("fixtures/leading-blank-line.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    assert_eq!(map.file_name, "leading-blank-line");
+    assert_eq!(map.preferred_load_addr, 0x10000000);
+    assert_eq!(map.sections.len(), 2);
+    assert_eq!(map.functions.len(), 1);
+}
+
+#[test]
+fn parse_anonymous_section() {
+    let map_string = std::fs::read_to_string("fixtures/anonymous-comdat.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    assert_eq!(map.sections.len(), 3);
+
+    let anonymous = &map.sections[1];
+    assert_eq!(anonymous.name, "/* anonymous */");
+    assert!(matches!(anonymous.class, Class::Code));
+    assert_eq!(anonymous.len, 0x20);
+    assert!(anonymous.is_synthetic());
+    assert!(!map.sections[0].is_synthetic());
+}
+
+#[test]
+fn parse_split_rva_base() {
+    let map_string = std::fs::read_to_string("fixtures/split-rva-base.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    assert_eq!(map.publics_layout, PublicsLayout::SplitRvaBase);
+
+    assert_eq!(map.functions[1].symbol, "_main");
+    assert_eq!(map.functions[1].rva.0, 0x1000);
+    assert!(matches!(
+        &map.functions[1].libobj,
+        LibObject::LibObj(None, obj) if obj == "main.obj"
+    ));
+    assert_eq!(map.functions[1].flags, SymbolFlags::IS_FUNCTION);
+
+    assert_eq!(map.static_symbols[0].rva.0, 0x1080);
+
+    // the value of an absolute symbol isn't an rva
+    let absolute = map_string.replace(
+        "00000000 00000000     <absolute>",
+        "00000005 00000000     <absolute>",
+    );
+    let map = MapFile::load(&absolute).unwrap();
+    assert_eq!(map.functions[0].rva.0, 0);
+
+    let mismatched = map_string.replace("00001000 10000000 f", "00001000 20000000 f");
+    let error = MapFile::load(&mismatched).err().unwrap();
+    assert_eq!(
+        error.to_string(),
+        "base 20000000 doesn't match the preferred load address 10000000 (line 14)"
+    );
+}
+
+#[test]
+fn parse_single_publics_block() {
+    let map_string = std::fs::read_to_string("fixtures/single-block.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    assert!(map.entrypoint.is_none());
+    assert!(map.static_symbols.is_empty());
+    assert_eq!(map.functions.len(), 3);
+
+    // the last line has no trailing newline and still makes it in
+    assert_eq!(map.functions[2].symbol, "_last");
+    assert_eq!(map.functions[2].rva.0, 0x1080);
+}
+
+#[test]
+fn load_partial() {
+    let map_string = std::fs::read_to_string("fixtures/corrupt-publics.map").unwrap();
+
+    assert!(MapFile::load(&map_string).is_err());
+
+    let (map, error) = MapFile::load_partial(&map_string);
+    let map = map.unwrap();
+
+    assert!(matches!(
+        error.unwrap(),
+        ParseError::BadAddress { line: 14, token, .. } if token == "1000GG80"
+    ));
+    assert_eq!(map.sections.len(), 1);
+    assert_eq!(
+        map.functions.iter().map(|x| &*x.symbol).collect::<Vec<_>>(),
+        ["_first", "_second"]
+    );
+
+    let (map, error) = MapFile::load_partial(" no-header\n");
+    assert!(map.is_none());
+    assert!(error.is_some());
+}
+
+#[test]
+fn parse_libobj_with_spaces() {
+    let map_string = std::fs::read_to_string("fixtures/libobj-spaces.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    assert!(matches!(
+        &map.functions[0].libobj,
+        LibObject::LibObj(Some(lib), obj) if lib == "third party\\zlib.lib" && obj == "inflate.obj"
+    ));
+    assert_eq!(map.functions[0].flags, SymbolFlags::IS_FUNCTION);
+
+    assert!(matches!(
+        &map.static_symbols[0].libobj,
+        LibObject::LibObj(None, obj) if obj == "my objects\\local helper.obj"
+    ));
+}
+
+#[test]
+fn parse_error_byte_offset() {
+    let map_string = std::fs::read_to_string("fixtures/corrupt-publics.map").unwrap();
+
+    let error = MapFile::load(&map_string).err().unwrap();
+
+    assert_eq!(error.line(), 14);
+    assert_eq!(&map_string[error.span()], "1000GG80");
+}
+
+#[test]
+fn parse_large_segment() {
+    let map_string = std::fs::read_to_string("fixtures/large-segment.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    assert_eq!(map.sections[1].addr.seg, 70000);
+    assert_eq!(map.functions[1].addr.seg, 70000);
+    assert_eq!(map.functions[1].addr.addr, 0x10);
+}
+
+#[test]
+fn parse_with_linker_preamble() {
+    let map_string = std::fs::read_to_string("fixtures/linker-preamble.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    assert_eq!(map.file_name, "linker-preamble");
+    assert_eq!(map.preferred_load_addr, 0x10000000);
+    assert_eq!(map.functions.len(), 1);
+
+    let error = MapFile::load("Microsoft (R) Incremental Linker\n\nLINK : fatal error\n")
+        .err()
+        .unwrap();
+    assert!(error.to_string().starts_with("no map header found"));
+}
+
+#[test]
+fn function_rva_plus_base() {
+    let map_string = std::fs::read_to_string("fixtures/unplaced.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    assert!(matches!(map.functions[0].libobj, LibObject::Absolute));
+    assert_eq!(map.functions[0].rva_plus_base(&map), 0);
+    assert_eq!(map.functions[1].symbol, "_optional_hook");
+    assert_eq!(map.functions[1].rva_plus_base(&map), 0);
+    assert_eq!(map.functions[2].rva_plus_base(&map), 0x10001000);
+    assert_eq!(map.static_symbols[0].rva_plus_base(&map), 0x10001080);
+}
+
+#[test]
+fn parse_lenient_missing_class() {
+    let map_string = std::fs::read_to_string("fixtures/missing-class.map").unwrap();
+
+    let error = MapFile::load(&map_string).err().unwrap();
+    assert_eq!(error.to_string(), "no class was found (line 9)");
+
+    let options = ParseOptions {
+        lenient: true,
+        ..Default::default()
+    };
+    let map = MapFile::load_with(&map_string, &options).unwrap();
+
+    assert_eq!(map.sections.len(), 3);
+    assert_eq!(map.sections[1].name, ".rdata");
+    assert_eq!(map.sections[1].class, Class::Unknown);
+    assert_eq!(map.sections[2].class, Class::Data);
+    assert_eq!(map.functions.len(), 2);
+}
+
+#[test]
+fn parse_decimal_rva() {
+    let map_string = std::fs::read_to_string("fixtures/decimal-rva.map").unwrap();
+
+    let options = ParseOptions {
+        rva_radix: Radix::Decimal,
+        ..Default::default()
+    };
+    let map = MapFile::load_with(&map_string, &options).unwrap();
+
+    assert_eq!(map.functions[0].rva.0, 0x1000);
+    // marked as hex, so the radix doesn't apply
+    assert_eq!(map.functions[1].rva.0, 0x1040);
+    assert_eq!(map.static_symbols[0].rva.0, 0x1080);
+
+    // the same digits read as hex are way off
+    let map = MapFile::load(&map_string).unwrap();
+    assert_eq!(map.functions[0].rva.0, 0x268439552 - 0x10000000);
+}
+
+#[test]
+fn parse_options_combined() {
+    let options = ParseOptions::new().lenient(true).rva_radix(Radix::Decimal);
+
+    let map_string = std::fs::read_to_string("fixtures/decimal-rva.map").unwrap();
+    let map = MapFile::load_with(&map_string, &options).unwrap();
+    assert_eq!(map.functions[0].rva.0, 0x1000);
+
+    let options = options.rva_radix(Radix::Hex);
+
+    let map_string = std::fs::read_to_string("fixtures/missing-class.map").unwrap();
+    let map = MapFile::load_with(&map_string, &options).unwrap();
+    assert_eq!(map.sections[1].class, Class::Unknown);
+    assert_eq!(map.functions[0].rva.0, 0x1000);
+}
+
+#[test]
+fn parse_malformed_rows_error() {
+    let map_string = std::fs::read_to_string("fixtures/mapinfo-exports.map").unwrap();
+
+    let truncated = map_string.replace(" 0001:00000040       _Create", " 0001       _Create");
+    let error = MapFile::load(&truncated).err().unwrap();
+    assert!(matches!(
+        error,
+        ParseError::BadAddress { line: 16, token, .. } if token == "0001"
+    ));
+
+    let truncated = map_string.replace(" 0002:00000000 00000200H", " 0002: 00000200H");
+    let error = MapFile::load(&truncated).err().unwrap();
+    assert!(matches!(
+        error,
+        ParseError::BadAddress { line: 9, token, .. } if token == "0002:"
+    ));
+
+    let no_class = map_string.replace(".rdata                  DATA", ".rdata");
+    let error = MapFile::load(&no_class).err().unwrap();
+    assert!(matches!(
+        error,
+        ParseError::UnexpectedSectionClass {
+            line: 9,
+            class: None,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn parse_section_classes() {
+    let map_string = std::fs::read_to_string("fixtures/mapinfo-exports.map").unwrap();
+
+    for (class, expected) in [
+        ("BSS", Class::Bss),
+        ("CONST", Class::Const),
+        ("TLS", Class::Tls),
+        ("STACK", Class::Stack),
+        ("DAT", Class::Other("DAT".into())),
+    ] {
+        let map_string = map_string.replace(
+            ".rdata                  DATA",
+            &format!(".rdata                  {}", class),
+        );
+
+        let map = MapFile::load(&map_string).unwrap();
+        let rdata = map.sections.iter().find(|x| x.name == ".rdata").unwrap();
+
+        assert_eq!(rdata.class, expected);
     }
+}
+
+#[test]
+fn section_contains() {
+    let section = Section {
+        name: ".text".into(),
+        class: Class::Code,
+        addr: Address {
+            seg: 1,
+            addr: 0x100,
+        },
+        len: 0x20,
+    };
+
+    assert_eq!(section.end(), 0x120);
+    assert!(section.contains(&Address {
+        seg: 1,
+        addr: 0x100
+    }));
+    assert!(section.contains(&Address {
+        seg: 1,
+        addr: 0x11f
+    }));
+    assert!(!section.contains(&Address {
+        seg: 1,
+        addr: 0x120
+    }));
+    assert!(!section.contains(&Address { seg: 1, addr: 0xff }));
+    assert!(!section.contains(&Address {
+        seg: 2,
+        addr: 0x100
+    }));
+
+    let empty = Section { len: 0, ..section };
+    assert!(!empty.contains(&Address {
+        seg: 1,
+        addr: 0x100
+    }));
+}
+
+#[test]
+fn parse_header_blank_lines() {
+    let map_string = std::fs::read_to_string("fixtures/mapinfo-exports.map").unwrap();
+
+    // no blank lines between the header lines, and a few extra in front of the sections
+    let squashed = map_string
+        .replacen("\n\n", "\n", 3)
+        .replacen("10000000\n", "10000000\n\n\n", 1);
+
+    let map = MapFile::load(&squashed).unwrap();
+
+    assert_eq!(map.file_name, "mapinfo-exports");
+    assert_eq!(map.timestamp_raw, 0x63e17200);
+    assert_eq!(map.preferred_load_addr, 0x10000000);
+    assert_eq!(map.sections.len(), 3);
+    assert_eq!(map.functions.len(), 6);
+}
+
+#[test]
+fn parse_bom() {
+    let map_string = std::fs::read_to_string("fixtures/bom.map").unwrap();
+    assert!(map_string.starts_with('\u{feff}'));
+
+    let map = MapFile::load(&map_string).unwrap();
 
+    assert_eq!(map.file_name, "bom");
+    assert_eq!(map.functions.len(), 6);
+
+    // offsets still count the bom
+    let broken = map_string.replace("10001080", "1000108z");
+    let error = MapFile::load(&broken).err().unwrap();
+    assert_eq!(&broken[error.span()], "1000108z");
+}
+
+#[test]
+fn parse_trailing_whitespace() {
+    let map_string = std::fs::read_to_string("fixtures/trailing-whitespace.map").unwrap();
+    assert!(!map_string.ends_with('\n'));
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    assert_eq!(map.functions.len(), 3);
+    assert_eq!(map.static_symbols.len(), 2);
+    assert_eq!(map.static_symbols[1].symbol, "_other_helper");
+}
+
+#[test]
+fn parse_missing_load_address() {
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+    let map_string = map_string.replace(" Preferred load address is 10000000\n", "");
+
+    let error = MapFile::load(&map_string).err().unwrap();
+
+    // the section table header, before any symbol
+    assert_eq!(
+        error.to_string(),
+        "preferred load address not found (line 6)"
+    );
+}
+
+#[test]
+fn parse_header() {
     let map_data = std::fs::read("csgo-x86.map").unwrap();
     let map_string = String::from_utf8(map_data).unwrap();
 
     let map = MapFile::load(&map_string).unwrap();
+    let header = MapFile::parse_header(&map_string).unwrap();
+
+    assert_eq!(header.file_name, map.file_name);
+    assert_eq!(header.timestamp, map.timestamp);
+    assert_eq!(header.timestamp_raw, map.timestamp_raw);
+    assert_eq!(header.preferred_load_addr, map.preferred_load_addr);
+
+    // nothing past the section table header is looked at
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+    let broken = map_string.replace("0001:00000040", "0001:xyz");
+    assert!(MapFile::load(&broken).is_err());
+    assert_eq!(MapFile::parse_header(&broken).unwrap().file_name, "aliases");
+
+    let error =
+        MapFile::parse_header(" aliases\n\n Timestamp is 63e17200 (Mon Feb  6 22:32:48 2023)\n")
+            .err()
+            .unwrap();
+    assert!(matches!(
+        error,
+        ParseError::MissingHeaderField {
+            field: "preferred load address",
+            ..
+        }
+    ));
+}
 
-    let mut output: String = Default::default();
-    let flags = msvc_demangler::DemangleFlags::NAME_ONLY;
+#[test]
+fn libobj_paths() {
+    assert_eq!(split_libobj("main.obj"), (None, "main.obj"));
+    assert_eq!(
+        split_libobj("libcmt.lib:crt0.obj"),
+        (Some("libcmt.lib"), "crt0.obj")
+    );
+    assert_eq!(split_libobj("a.lib:x:y.obj"), (Some("a.lib"), "x:y.obj"));
+    assert_eq!(
+        split_libobj(r"C:\build\obj\main.obj"),
+        (None, r"C:\build\obj\main.obj")
+    );
+    assert_eq!(
+        split_libobj(r"C:\sdk\foo.lib:D:\build\bar.obj"),
+        (Some(r"C:\sdk\foo.lib"), r"D:\build\bar.obj")
+    );
+
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+    let map_string = map_string.replace(" foo.obj", r" C:\sdk\foo.lib:D:\build\foo.obj");
 
-    for function in &map.functions {
-        output.push_str(
-            format!(
-                "{} {} {}\n",
-                function.rva.0 + map.preferred_load_addr,
-                fix_name_for_ida(
-                    &msvc_demangler::demangle(function.symbol, flags)
-                        .unwrap_or(function.symbol.to_owned())
-                ),
-                fix_mangled_symbol(function.symbol)
-            )
-            .as_str(),
-        );
-    }
+    let map = MapFile::load(&map_string).unwrap();
 
-    for symbol in &map.static_symbols {
-        output.push_str(
-            format!(
-                "{} {} {}\n",
-                symbol.rva.0 + map.preferred_load_addr,
-                fix_name_for_ida(
-                    &msvc_demangler::demangle(symbol.symbol, flags)
-                        .unwrap_or(symbol.symbol.to_owned())
-                ),
-                fix_mangled_symbol(symbol.symbol)
-            )
-            .as_str(),
-        );
+    assert!(matches!(
+        &map.functions[1].libobj,
+        LibObject::LibObj(Some(lib), obj) if lib == r"C:\sdk\foo.lib" && obj == r"D:\build\foo.obj"
+    ));
+}
+
+#[test]
+fn symbols_hash_and_compare() {
+    use std::collections::HashSet;
+
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+    let again = MapFile::load(&map_string).unwrap();
+
+    assert_eq!(map.functions, again.functions);
+    assert_eq!(map.sections, again.sections);
+
+    // the folded Release methods share one address
+    let addresses: HashSet<&Address> = map.functions.iter().map(|x| &x.addr).collect();
+    assert_eq!(addresses.len(), map.functions.len() - 1);
+
+    let functions: HashSet<&Function> = map.functions.iter().chain(&again.functions).collect();
+    assert_eq!(functions.len(), map.functions.len());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn symbol_source_lines() {
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    assert_eq!(map.functions[0].source_line, 14);
+    assert_eq!(map.functions[5].source_line, 19);
+    assert_eq!(map.static_symbols[0].source_line, 25);
+
+    let lines: Vec<&str> = map_string.lines().collect();
+    for symbol in map.symbols() {
+        assert!(lines[symbol.get().source_line - 1].contains(symbol.symbol()));
     }
 
-    std::fs::write("ida.sym", output).unwrap();
+    let streamed = MapFileOwned::from_reader(map_string.as_bytes()).unwrap();
+    assert_eq!(streamed.functions, map.functions);
+    assert_eq!(streamed.static_symbols, map.static_symbols);
+}
+
+#[test]
+fn parse_lenient() {
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+    let map_string = map_string.replace(
+        "0001:00000040       ?Release@Foo",
+        "0001:xyz       ?Release@Foo",
+    );
+    // cut off in the middle of the static symbols
+    let truncated = &map_string[..map_string.find("_local_helper").unwrap() + 6];
+
+    assert!(MapFile::parse(truncated).is_err());
+
+    let (map, errors) = MapFile::parse_lenient(truncated);
+    let map = map.unwrap();
+
+    assert_eq!(map.functions.len(), 5);
+    assert!(map.static_symbols.is_empty());
+    assert_eq!(errors.len(), 2);
+    assert!(matches!(
+        &errors[0],
+        ParseError::BadAddress { line: 15, .. }
+    ));
+    assert_eq!(errors[1].line(), 25);
+
+    let (map, errors) = MapFile::parse_lenient(" aliases\n");
+    assert!(map.is_none());
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn parse_x64() {
+    let map_string = std::fs::read_to_string("fixtures/x64.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    assert_eq!(map.preferred_load_addr, 0x140000000);
+    assert_eq!(map.functions.len(), 5);
+    assert_eq!(map.functions[0].rva.0, 0);
+    assert_eq!(map.functions[2].symbol, "?run@App@@QEAAXXZ");
+    assert_eq!(map.functions[2].rva.0, 0x1040);
+    assert_eq!(map.functions[2].rva_plus_base(&map), 0x140001040);
+    assert_eq!(map.functions[2].va(&map), 0x140001040);
+    assert_eq!(map.functions[0].va(&map), 0);
+    assert_eq!(map.static_symbols[0].rva.0, 0x1080);
+
+    // a base below the load address is an error instead of an underflow
+    let map_string = map_string.replace("0000000140001040", "0000000040001040");
+
+    let error = MapFile::load(&map_string).err().unwrap();
+    assert_eq!(
+        error.to_string(),
+        "rva+base 40001040 is below the preferred load address 140000000 (line 16)"
+    );
+}
+
+#[test]
+fn parse_tab_separated_columns() {
+    let map_string = std::fs::read_to_string("fixtures/mapinfo-exports.map").unwrap();
+
+    let map_string = map_string
+        .replace(
+            " 0002:00000000 00000200H .rdata ",
+            " 0002:00000000\t00000200H\t.rdata\t",
+        )
+        .replace(
+            " 0001:00000000       _DllMain@12      ",
+            " 0001:00000000\t_DllMain@12\t",
+        )
+        .replace("10001000 f   dllmain.obj", "10001000\tf\tdllmain.obj");
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    assert_eq!(map.sections[1].name, ".rdata");
+    assert_eq!(map.sections[1].class, Class::Data);
+
+    let dllmain = &map.functions[1];
+    assert_eq!(dllmain.symbol, "_DllMain@12");
+    assert_eq!(dllmain.rva.0, 0x1000);
+    assert!(dllmain.flags.contains(SymbolFlags::IS_FUNCTION));
+    assert!(matches!(&dllmain.libobj, LibObject::LibObj(None, obj) if obj == "dllmain.obj"));
+}
+
+#[test]
+fn data_row_detection() {
+    assert!(is_data_row(" 0001:00000000 00001000H .text CODE"));
+    assert!(is_data_row(
+        " 0003:0000111c       _g_flags    1000311c     main.obj"
+    ));
+    // no zero anywhere
+    assert!(is_data_row(" 1:1234abcd       _g    1234abcd     main.obj"));
+    assert!(!is_data_row(" Start         Length     Name"));
+    assert!(!is_data_row("  Address         Publics by Value"));
+    assert!(!is_data_row(""));
+    assert!(!is_data_row(" entry point at        0001:00000000"));
+    assert!(is_data_row(" 0001:xyz"));
+}
+
+#[test]
+fn parse_absolute_values() {
+    let map_string = std::fs::read_to_string("fixtures/mapinfo-exports.map").unwrap();
+    let map_string = map_string.replace(
+        " 0000:00000000       ___safe_se_handler_count   00000000     <absolute>",
+        " 0000:00010100       ___guard_flags             00010100     <absolute>",
+    );
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let guard_flags = &map.functions[0];
+    assert_eq!(guard_flags.symbol, "___guard_flags");
+    assert!(guard_flags.addr.is_absolute());
+    assert_eq!(guard_flags.addr.addr, 0x10100);
+    assert_eq!(guard_flags.rva.0, 0);
+    assert!(matches!(guard_flags.libobj, LibObject::Absolute));
+    assert!(!map.functions[1].addr.is_absolute());
 }
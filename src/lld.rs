@@ -0,0 +1,218 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use anyhow::{Context, Result};
+
+use crate::gnu::{class_of_section, parse_libobj};
+use crate::{
+    Address, Class, EncodingInfo, Function, MapFile, MapParseError, PublicsLayout, Rva, Section,
+    SymbolFlags, SymbolKind,
+};
+
+//
+// lld-link (`/lldmap`) writes one table, nesting input sections and symbols under their
+// output section by indentation. the addresses are rvas:
+//
+// Address  Size     Align Out     In      Symbol
+// <rva>    <size>   <align> <output_section>
+// <rva>    <size>   <align>         <object or archive(member)>:(<input_section>)
+// <rva>    00000000     0                 <symbol>
+//
+
+const HEADER: [&str; 6] = ["Address", "Size", "Align", "Out", "In", "Symbol"];
+
+/// whether `data` is the header line lld-link starts its map with
+pub(crate) fn is_header(data: &str) -> bool {
+    data.split_whitespace().eq(HEADER)
+}
+
+/// the output section being walked
+struct CurrentSection {
+    seg: u32,
+    rva: usize,
+    class: Class<'static>,
+}
+
+#[derive(Default)]
+struct LldParser<'a> {
+    sections: Vec<Section<'a>>,
+    functions: Vec<Function<'a>>,
+    section: Option<CurrentSection>,
+    object: Option<&'a str>,
+    // editor line number of the current line
+    line: usize,
+}
+
+impl<'a> LldParser<'a> {
+    fn parse_line(&mut self, data: &'a str) -> Result<()> {
+        let offset = |x: &str| x.as_ptr() as usize - data.as_ptr() as usize;
+        let mut columns = data.split_whitespace();
+
+        let (Some(rva), Some(size), Some(align)) = (columns.next(), columns.next(), columns.next())
+        else {
+            return Ok(());
+        };
+
+        let rva = usize::from_str_radix(rva, 16).context("unable to parse address")?;
+        let size = usize::from_str_radix(size, 16).context("unable to parse size")?;
+
+        // everything after the align column, indented by how deep it is nested. names may
+        // contain spaces once demangled, so this runs to the end of the line
+        let name = columns.next().context("no name was found")?;
+        let rest = data[offset(name)..].trim_end();
+        let indent = offset(name) - (offset(align) + align.len() + 1);
+
+        match indent {
+            // an output section
+            0..=7 => {
+                let seg = self.sections.len() as u32 + 1;
+                let class = class_of_section(rest);
+
+                self.sections.push(Section {
+                    name: rest.into(),
+                    class: class.clone(),
+                    addr: Address { seg, addr: 0 },
+                    len: size,
+                });
+
+                self.section = Some(CurrentSection { seg, rva, class });
+                self.object = None;
+            }
+            // an input section, naming the object the following symbols come from
+            8..=15 => {
+                let (object, _) = rest
+                    .rsplit_once(":(")
+                    .context("input section is not in `<object>:(<section>)` form")?;
+
+                self.object = Some(object);
+            }
+            _ => {
+                let (Some(section), Some(object)) = (&self.section, self.object) else {
+                    return Ok(());
+                };
+
+                self.functions.push(Function {
+                    kind: SymbolKind::Public,
+                    symbol: rest.into(),
+                    addr: Address {
+                        seg: section.seg,
+                        addr: rva
+                            .checked_sub(section.rva)
+                            .context("symbol is in front of its section")?,
+                    },
+                    rva: Rva(rva),
+                    flags: match section.class {
+                        Class::Code => SymbolFlags::IS_FUNCTION,
+                        _ => SymbolFlags::empty(),
+                    },
+                    unknown_flags: vec![],
+                    libobj: parse_libobj(object),
+                    source_line: self.line,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn build(mut self) -> MapFile<'a> {
+        self.functions.sort_by_key(|x| x.rva.0);
+
+        MapFile {
+            file_name: "".into(),
+            entrypoint: None,
+            // the map only has rvas, so symbols are placed relative to 0
+            preferred_load_addr: 0,
+            timestamp: "".into(),
+            timestamp_raw: 0,
+            sections: self.sections,
+            functions: self.functions,
+            static_symbols: vec![],
+            exports: vec![],
+            publics_layout: PublicsLayout::default(),
+            encoding: EncodingInfo::default(),
+        }
+    }
+}
+
+/// parses a map written by lld-link's `/lldmap`. like GNU ld's, it has no module name,
+/// timestamp or entry point, and every symbol ends up in `functions`
+pub(crate) fn parse(input: &str) -> Result<MapFile<'_>, MapParseError> {
+    let start = input
+        .lines()
+        .position(is_header)
+        .context("no `Address Size Align Out In Symbol` line found")
+        .map_err(|error| MapParseError {
+            line: 1,
+            byte_offset: 0,
+            len: 0,
+            error,
+        })?;
+
+    let mut parser = LldParser::default();
+
+    for (line, data) in input.lines().enumerate().skip(start + 1) {
+        parser.line = line + 1;
+        parser.parse_line(data).map_err(|error| MapParseError {
+            line: line + 1,
+            byte_offset: data.as_ptr() as usize - input.as_ptr() as usize,
+            len: data.len(),
+            error,
+        })?;
+    }
+
+    Ok(parser.build())
+}
+
+#[test]
+fn parse_lld() {
+    let map_string = std::fs::read_to_string("fixtures/lld.map").unwrap();
+
+    assert_eq!(crate::MapFormat::detect(&map_string), crate::MapFormat::Lld);
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let sections: Vec<&str> = map.sections.iter().map(|x| &*x.name).collect();
+    assert_eq!(sections, [".text", ".rdata", ".data"]);
+    assert_eq!(map.sections[0].class, Class::Code);
+    assert_eq!(map.sections[0].len, 0x60);
+    assert_eq!(map.sections[2].class, Class::Data);
+
+    let symbols: Vec<&str> = map.functions.iter().map(|x| &*x.symbol).collect();
+    assert_eq!(
+        symbols,
+        ["main", "void __cdecl helper(void)", "printf", "g_counter"]
+    );
+
+    let helper = &map.functions[1];
+    assert_eq!(helper.rva.0, 0x1020);
+    assert_eq!(helper.addr, Address { seg: 1, addr: 0x20 });
+    assert_eq!(helper.flags, SymbolFlags::IS_FUNCTION);
+    assert_eq!(helper.source_line, 5);
+    // no image base in the map, so the va is the rva
+    assert_eq!(helper.va(&map), 0x1020);
+    assert_eq!(
+        map.symbol_at_va(0x1020, None).unwrap().symbol(),
+        helper.symbol
+    );
+    assert!(matches!(&helper.libobj, crate::LibObject::LibObj(None, obj) if obj == "main.obj"));
+
+    let printf = &map.functions[2];
+    assert!(matches!(
+        &printf.libobj,
+        crate::LibObject::LibObj(Some(lib), obj) if lib == "libcmt.lib" && obj == "printf.obj"
+    ));
+
+    let g_counter = &map.functions[3];
+    assert_eq!(g_counter.addr, Address { seg: 3, addr: 0 });
+    assert!(g_counter.flags.is_empty());
+    assert_eq!(
+        map.symbol_at(0x1028).unwrap().symbol,
+        "void __cdecl helper(void)"
+    );
+
+    let error = MapFile::load(&map_string.replace("00001030 00000000", "0000103g 00000000"))
+        .err()
+        .unwrap();
+    assert_eq!(error.line(), 7);
+}
@@ -0,0 +1,130 @@
+//! converts a map file into something a disassembler or debugger can import
+//!
+//! `mapparse <input.map> [--format ida|ghidra|x64dbg|json|binaryninja] [--demangle none|name|full] [--dedup all|first|longest] [--out <file>]`
+
+use std::process::ExitCode;
+
+use mapparse::{DedupPolicy, DemangleFlags, DemangleMode, ExportOptions, MapFileOwned};
+
+const USAGE: &str = "usage: mapparse <input.map> [--format ida|ghidra|x64dbg|json|binaryninja] \
+                     [--demangle none|name|full] [--dedup all|first|longest] [--out <file>]";
+
+#[derive(Clone, Copy)]
+enum Format {
+    Ida,
+    Ghidra,
+    X64dbg,
+    Json,
+    BinaryNinja,
+}
+
+struct Args {
+    input: String,
+    format: Format,
+    demangle: (DemangleMode, DemangleFlags),
+    dedup: DedupPolicy,
+    out: Option<String>,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut input = None;
+    let mut format = Format::Ida;
+    let mut demangle = (DemangleMode::Lenient, DemangleFlags::NAME_ONLY);
+    let mut dedup = DedupPolicy::KeepAll;
+    let mut out = None;
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("{} needs a value", arg));
+
+        match arg.as_str() {
+            "--format" => {
+                format = match value()?.as_str() {
+                    "ida" => Format::Ida,
+                    "ghidra" => Format::Ghidra,
+                    "x64dbg" => Format::X64dbg,
+                    "json" => Format::Json,
+                    "binaryninja" => Format::BinaryNinja,
+                    x => return Err(format!("unknown format {}", x)),
+                }
+            }
+            "--demangle" => {
+                demangle = match value()?.as_str() {
+                    "none" => (DemangleMode::Never, DemangleFlags::NAME_ONLY),
+                    "name" => (DemangleMode::Lenient, DemangleFlags::NAME_ONLY),
+                    "full" => (DemangleMode::Lenient, DemangleFlags::COMPLETE),
+                    x => return Err(format!("unknown demangle mode {}", x)),
+                }
+            }
+            "--dedup" => {
+                dedup = match value()?.as_str() {
+                    "all" => DedupPolicy::KeepAll,
+                    "first" => DedupPolicy::First,
+                    "longest" => DedupPolicy::LongestName,
+                    x => return Err(format!("unknown dedup policy {}", x)),
+                }
+            }
+            "--out" => out = Some(value()?),
+            x if x.starts_with("--") => return Err(format!("unknown option {}", x)),
+            _ if input.is_none() => input = Some(arg),
+            _ => return Err(format!("unexpected argument {}", arg)),
+        }
+    }
+
+    Ok(Args {
+        input: input.ok_or("no input map given")?,
+        format,
+        demangle,
+        dedup,
+        out,
+    })
+}
+
+fn run(args: Args) -> Result<(), String> {
+    let input =
+        std::fs::read(&args.input).map_err(|x| format!("unable to read {}: {}", args.input, x))?;
+
+    let map = MapFileOwned::load_bytes(&input).map_err(|x| format!("{}: {:#}", args.input, x))?;
+
+    let options = ExportOptions {
+        demangle: args.demangle.0,
+        demangle_flags: Some(args.demangle.1),
+        dedup: args.dedup,
+        ..Default::default()
+    };
+
+    let output = match args.format {
+        Format::Ida => map.export_ida(&options),
+        Format::Ghidra => map.export_ghidra(&options),
+        Format::X64dbg => map.export_x64dbg(&options),
+        Format::Json => map.export_json(&options),
+        Format::BinaryNinja => map.export_binaryninja(&options),
+    };
+
+    match args.out {
+        Some(out) => {
+            std::fs::write(&out, output).map_err(|x| format!("unable to write {}: {}", out, x))
+        }
+        None => {
+            print!("{}", output);
+            Ok(())
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(error) => {
+            eprintln!("{}\n{}", error, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("{}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
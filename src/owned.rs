@@ -0,0 +1,336 @@
+use alloc::borrow::Cow;
+use alloc::string::String;
+
+use crate::{Class, Export, LibObject, MapFile, ParseError, Section, Symbol};
+
+/// a map that does not borrow from the text it was parsed from, so it can be returned from a
+/// function or kept in a cache after the input buffer is gone. get one from
+/// [`MapFile::into_owned`] or [`MapFileOwned::load_bytes`]
+pub type MapFileOwned = MapFile<'static>;
+
+pub(crate) fn owned(x: Cow<str>) -> Cow<'static, str> {
+    Cow::Owned(x.into_owned())
+}
+
+impl<'a> Class<'a> {
+    pub fn into_owned(self) -> Class<'static> {
+        match self {
+            Class::Code => Class::Code,
+            Class::Data => Class::Data,
+            Class::Bss => Class::Bss,
+            Class::Const => Class::Const,
+            Class::Tls => Class::Tls,
+            Class::Stack => Class::Stack,
+            Class::Unknown => Class::Unknown,
+            Class::Other(class) => Class::Other(owned(class)),
+        }
+    }
+}
+
+impl<'a> Section<'a> {
+    pub fn into_owned(self) -> Section<'static> {
+        Section {
+            name: owned(self.name),
+            class: self.class.into_owned(),
+            addr: self.addr,
+            len: self.len,
+        }
+    }
+}
+
+impl<'a> LibObject<'a> {
+    pub fn into_owned(self) -> LibObject<'static> {
+        match self {
+            LibObject::LibObj(lib, obj) => LibObject::LibObj(lib.map(owned), owned(obj)),
+            LibObject::Absolute => LibObject::Absolute,
+        }
+    }
+}
+
+impl<'a> Symbol<'a> {
+    pub fn into_owned(self) -> Symbol<'static> {
+        Symbol {
+            kind: self.kind,
+            symbol: owned(self.symbol),
+            addr: self.addr,
+            rva: self.rva,
+            flags: self.flags,
+            unknown_flags: self.unknown_flags,
+            libobj: self.libobj.into_owned(),
+            source_line: self.source_line,
+        }
+    }
+}
+
+impl<'a> Export<'a> {
+    pub fn into_owned(self) -> Export<'static> {
+        Export {
+            ordinal: self.ordinal,
+            symbol: owned(self.symbol),
+            name: self.name.map(owned),
+        }
+    }
+}
+
+impl<'a> MapFile<'a> {
+    /// detaches the map from the input buffer
+    pub fn into_owned(self) -> MapFileOwned {
+        MapFile {
+            file_name: owned(self.file_name),
+            entrypoint: self.entrypoint,
+            preferred_load_addr: self.preferred_load_addr,
+            timestamp: owned(self.timestamp),
+            timestamp_raw: self.timestamp_raw,
+            sections: self.sections.into_iter().map(Section::into_owned).collect(),
+            functions: self.functions.into_iter().map(Symbol::into_owned).collect(),
+            static_symbols: self
+                .static_symbols
+                .into_iter()
+                .map(Symbol::into_owned)
+                .collect(),
+            exports: self.exports.into_iter().map(Export::into_owned).collect(),
+            publics_layout: self.publics_layout,
+            encoding: self.encoding,
+        }
+    }
+}
+
+/// what [`MapFileOwned::load_bytes`] had to do to turn the input into text, so tools can warn
+/// that some symbol names may be garbled
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EncodingInfo {
+    /// the text encoding the input was read as
+    pub text: TextEncoding,
+    /// the input started with a byte order mark, which was dropped
+    pub had_bom: bool,
+    /// how many invalid byte sequences (or unpaired utf-16 surrogates) were replaced with
+    /// `U+FFFD`
+    pub lossy_replacements: usize,
+}
+
+/// see [`EncodingInfo::text`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TextEncoding {
+    #[default]
+    Utf8,
+    /// only recognized by its byte order mark
+    Utf16Le,
+    /// only recognized by its byte order mark
+    Utf16Be,
+}
+
+/// decodes utf-16 in the given byte order, replacing unpaired surrogates like
+/// `String::from_utf8_lossy` does for utf-8. a trailing odd byte counts as one replacement
+fn decode_utf16_lossy(input: &[u8], from_bytes: fn([u8; 2]) -> u16) -> (String, usize) {
+    let (units, rest) = input.as_chunks::<2>();
+    let mut replacements = rest.len();
+
+    let mut text: String = char::decode_utf16(units.iter().map(|&x| from_bytes(x)))
+        .map(|x| {
+            x.unwrap_or_else(|_| {
+                replacements += 1;
+                char::REPLACEMENT_CHARACTER
+            })
+        })
+        .collect();
+
+    if !rest.is_empty() {
+        text.push(char::REPLACEMENT_CHARACTER);
+    }
+
+    (text, replacements)
+}
+
+impl MapFileOwned {
+    /// parses raw file contents. invalid utf-8 is replaced rather than rejected,
+    /// as the occasional mangled symbol shouldn't cost us the whole map. utf-16 input, as
+    /// some older windows tools write it, is recognized by its byte order mark.
+    /// what was done is recorded in [`MapFile::encoding`]
+    pub fn load_bytes(input: &[u8]) -> Result<Self, ParseError> {
+        let (text, encoding) = if let Some(input) = input.strip_prefix(b"\xff\xfe") {
+            let (text, lossy_replacements) = decode_utf16_lossy(input, u16::from_le_bytes);
+
+            let encoding = EncodingInfo {
+                text: TextEncoding::Utf16Le,
+                had_bom: true,
+                lossy_replacements,
+            };
+
+            (Cow::Owned(text), encoding)
+        } else if let Some(input) = input.strip_prefix(b"\xfe\xff") {
+            let (text, lossy_replacements) = decode_utf16_lossy(input, u16::from_be_bytes);
+
+            let encoding = EncodingInfo {
+                text: TextEncoding::Utf16Be,
+                had_bom: true,
+                lossy_replacements,
+            };
+
+            (Cow::Owned(text), encoding)
+        } else {
+            let (input, had_bom) = match input.strip_prefix(b"\xef\xbb\xbf") {
+                Some(input) => (input, true),
+                None => (input, false),
+            };
+
+            let lossy_replacements = input
+                .utf8_chunks()
+                .filter(|x| !x.invalid().is_empty())
+                .count();
+
+            let encoding = EncodingInfo {
+                text: TextEncoding::Utf8,
+                had_bom,
+                lossy_replacements,
+            };
+
+            (String::from_utf8_lossy(input), encoding)
+        };
+
+        let mut map = MapFile::load(&text)?.into_owned();
+        map.encoding = encoding;
+
+        Ok(map)
+    }
+}
+
+impl<'a> TryFrom<&'a str> for MapFile<'a> {
+    type Error = ParseError;
+
+    fn try_from(value: &'a str) -> Result<Self, ParseError> {
+        MapFile::load(value)
+    }
+}
+
+impl TryFrom<&[u8]> for MapFileOwned {
+    type Error = ParseError;
+
+    fn try_from(value: &[u8]) -> Result<Self, ParseError> {
+        MapFile::load_bytes(value)
+    }
+}
+
+#[test]
+fn into_owned_outlives_input() {
+    fn load(path: &str) -> MapFileOwned {
+        let map_string = std::fs::read_to_string(path).unwrap();
+        MapFile::load(&map_string).unwrap().into_owned()
+    }
+
+    let mut cache = std::collections::HashMap::new();
+    cache.insert("aliases.map", load("fixtures/aliases.map"));
+    cache.insert("mapinfo-exports.map", load("fixtures/mapinfo-exports.map"));
+
+    let map = &cache["mapinfo-exports.map"];
+    assert_eq!(map.file_name, "mapinfo-exports");
+    assert!(matches!(map.file_name, Cow::Owned(_)));
+    assert_eq!(map.exports[0].exported_name(), "CreateInterface");
+    assert_eq!(
+        cache["aliases.map"].static_symbols[0].symbol,
+        "_local_helper"
+    );
+}
+
+#[test]
+fn try_from_bytes() {
+    let map_data = std::fs::read("fixtures/mapinfo-exports.map").unwrap();
+
+    let map: MapFileOwned = map_data.as_slice().try_into().unwrap();
+    drop(map_data);
+
+    assert_eq!(map.file_name, "mapinfo-exports");
+    assert_eq!(map.functions.len(), 6);
+    assert_eq!(map.functions[1].symbol, "_DllMain@12");
+}
+
+#[test]
+fn load_bytes_encoding_info() {
+    let mut map_data = b"\xef\xbb\xbf".to_vec();
+    map_data.extend(std::fs::read("fixtures/mapinfo-exports.map").unwrap());
+
+    let map = MapFileOwned::load_bytes(&map_data).unwrap();
+    assert_eq!(
+        map.encoding,
+        EncodingInfo {
+            text: TextEncoding::Utf8,
+            had_bom: true,
+            lossy_replacements: 0
+        }
+    );
+
+    let mut map_data = std::fs::read("fixtures/mapinfo-exports.map").unwrap();
+    let idx = map_data
+        .windows(10)
+        .position(|x| x == b"_g_counter")
+        .unwrap();
+    map_data[idx + 4] = 0xff;
+
+    let map = MapFileOwned::load_bytes(&map_data).unwrap();
+    assert_eq!(
+        map.encoding,
+        EncodingInfo {
+            text: TextEncoding::Utf8,
+            had_bom: false,
+            lossy_replacements: 1
+        }
+    );
+    assert_eq!(map.functions[5].symbol, "_g_c\u{fffd}unter");
+}
+
+#[test]
+fn load_bytes_utf16() {
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+    let map = MapFile::load(&map_string).unwrap();
+
+    let map_data = std::fs::read("fixtures/utf16le.map").unwrap();
+    let utf16 = MapFileOwned::load_bytes(&map_data).unwrap();
+
+    assert_eq!(
+        utf16.encoding,
+        EncodingInfo {
+            text: TextEncoding::Utf16Le,
+            had_bom: true,
+            lossy_replacements: 0
+        }
+    );
+    assert_eq!(utf16.functions, map.functions);
+    assert_eq!(utf16.static_symbols, map.static_symbols);
+
+    // the same text big endian
+    let mut map_data: Vec<u8> = map_data.chunks(2).flat_map(|x| [x[1], x[0]]).collect();
+    assert_eq!(&map_data[..2], b"\xfe\xff");
+
+    let utf16 = MapFileOwned::load_bytes(&map_data).unwrap();
+    assert_eq!(utf16.encoding.text, TextEncoding::Utf16Be);
+    assert_eq!(utf16.functions, map.functions);
+
+    // an unpaired surrogate in a symbol name
+    let idx = map_data
+        .windows(4)
+        .position(|x| x == b"\x00_\x00l")
+        .unwrap();
+    map_data[idx..idx + 2].copy_from_slice(b"\xd8\x00");
+
+    let utf16 = MapFileOwned::load_bytes(&map_data).unwrap();
+    assert_eq!(utf16.encoding.lossy_replacements, 1);
+    assert_eq!(utf16.static_symbols[0].symbol, "\u{fffd}local_helper");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trip() {
+    let map_string = std::fs::read_to_string("csgo-x86.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+    let json = serde_json::to_string(&map).unwrap();
+    let functions = map.functions.len();
+
+    // the deserialized map owns its strings, so it outlives the input
+    let restored: MapFileOwned = serde_json::from_str(&json).unwrap();
+    drop(map_string);
+
+    assert_eq!(restored.functions.len(), functions);
+    assert_eq!(serde_json::to_string(&restored).unwrap(), json);
+}
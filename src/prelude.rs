@@ -0,0 +1,25 @@
+//! the types and traits most users of the crate need, in one import
+//!
+//! ```
+//! use mapparse::prelude::*;
+//!
+//! let input = std::fs::read_to_string("csgo-x86.map").unwrap();
+//! let map = MapFile::try_from(input.as_str()).unwrap();
+//!
+//! for function in &map.functions {
+//!     if let LibObject::LibObj(_, obj) = &function.libobj {
+//!         println!("{} from {}", function.symbol, obj);
+//!     }
+//! }
+//! ```
+
+pub use crate::{
+    Address, Class, Export, Function, LibObject, MapFile, MapFileOwned, MapFormat, MapHeader,
+    MapParseError, ParseError, ParseOptions, Radix, Rva, Section, StaticSymbol, Symbol,
+    SymbolFlags, SymbolKind, SymbolRef,
+};
+#[cfg(feature = "std")]
+pub use crate::{
+    AddressFormat, DedupPolicy, DemangleFlags, DemangleMode, ExportOptions, MapSummary,
+    NameSanitizer, SymbolIndex,
+};
@@ -0,0 +1,224 @@
+use std::path::PathBuf;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::{Class, LibObject, MapFile, MapFileOwned, ParseError, Section, Symbol, SymbolFlags};
+
+/// a public or static symbol, copied out of the map
+#[pyclass(
+    name = "Symbol",
+    module = "mapparse",
+    get_all,
+    frozen,
+    skip_from_py_object
+)]
+#[derive(Clone)]
+pub struct PySymbol {
+    /// the mangled name
+    name: String,
+    seg: u32,
+    /// offset into the segment
+    offset: usize,
+    rva: usize,
+    /// rva plus the preferred load address
+    va: usize,
+    is_function: bool,
+    /// `None` for absolute symbols
+    lib: Option<String>,
+    obj: Option<String>,
+}
+
+impl PySymbol {
+    fn new(symbol: &Symbol, map: &MapFile) -> Self {
+        let (lib, obj) = match &symbol.libobj {
+            LibObject::LibObj(lib, obj) => {
+                (lib.as_deref().map(str::to_owned), Some(obj.to_string()))
+            }
+            LibObject::Absolute => (None, None),
+        };
+
+        PySymbol {
+            name: symbol.symbol.to_string(),
+            seg: symbol.addr.seg,
+            offset: symbol.addr.addr,
+            rva: symbol.rva.0,
+            va: symbol.rva_plus_base(map),
+            is_function: symbol.flags.contains(SymbolFlags::IS_FUNCTION),
+            lib,
+            obj,
+        }
+    }
+}
+
+#[pymethods]
+impl PySymbol {
+    fn __repr__(&self) -> String {
+        format!("<Symbol {} at {:#x}>", self.name, self.va)
+    }
+}
+
+/// a section from the start of the map
+#[pyclass(
+    name = "Section",
+    module = "mapparse",
+    get_all,
+    frozen,
+    skip_from_py_object
+)]
+#[derive(Clone)]
+pub struct PySection {
+    name: String,
+    /// `CODE`, `DATA` and so on, as written in the map. empty if the row had none
+    class: String,
+    seg: u32,
+    /// offset into the segment
+    offset: usize,
+    len: usize,
+}
+
+impl From<&Section<'_>> for PySection {
+    fn from(section: &Section) -> Self {
+        PySection {
+            name: section.name.to_string(),
+            class: match &section.class {
+                Class::Code => "CODE",
+                Class::Data => "DATA",
+                Class::Bss => "BSS",
+                Class::Const => "CONST",
+                Class::Tls => "TLS",
+                Class::Stack => "STACK",
+                Class::Unknown => "",
+                Class::Other(class) => class,
+            }
+            .to_owned(),
+            seg: section.addr.seg,
+            offset: section.addr.addr,
+            len: section.len,
+        }
+    }
+}
+
+#[pymethods]
+impl PySection {
+    fn __repr__(&self) -> String {
+        format!(
+            "<Section {} {:04x}:{:08x}+{:#x}>",
+            self.name, self.seg, self.offset, self.len
+        )
+    }
+}
+
+/// a parsed map. everything is copied out of the rust side, so the lists can be kept and
+/// modified freely
+#[pyclass(
+    name = "MapFile",
+    module = "mapparse",
+    get_all,
+    frozen,
+    skip_from_py_object
+)]
+pub struct PyMapFile {
+    file_name: String,
+    timestamp: String,
+    timestamp_raw: u32,
+    preferred_load_addr: usize,
+    /// `(seg, offset)` of the entry point, `None` for dlls without one
+    entrypoint: Option<(u32, usize)>,
+    sections: Vec<PySection>,
+    functions: Vec<PySymbol>,
+    static_symbols: Vec<PySymbol>,
+}
+
+impl From<&MapFile<'_>> for PyMapFile {
+    fn from(map: &MapFile) -> Self {
+        PyMapFile {
+            file_name: map.file_name.to_string(),
+            timestamp: map.timestamp.to_string(),
+            timestamp_raw: map.timestamp_raw,
+            preferred_load_addr: map.preferred_load_addr,
+            entrypoint: map.entrypoint.as_ref().map(|x| (x.seg, x.addr)),
+            sections: map.sections.iter().map(PySection::from).collect(),
+            functions: map
+                .functions
+                .iter()
+                .map(|x| PySymbol::new(x, map))
+                .collect(),
+            static_symbols: map
+                .static_symbols
+                .iter()
+                .map(|x| PySymbol::new(x, map))
+                .collect(),
+        }
+    }
+}
+
+#[pymethods]
+impl PyMapFile {
+    fn __repr__(&self) -> String {
+        format!(
+            "<MapFile {} with {} functions>",
+            self.file_name,
+            self.functions.len()
+        )
+    }
+}
+
+impl From<ParseError> for PyErr {
+    fn from(error: ParseError) -> Self {
+        PyValueError::new_err(error.to_string())
+    }
+}
+
+/// parses a map from a path (`str` or `os.PathLike`) or from the map text itself, told apart by
+/// the text spanning more than one line. raises `ValueError` if the map doesn't parse and
+/// `OSError` if the file can't be read
+#[pyfunction]
+fn parse(path_or_str: &Bound<'_, PyAny>) -> PyResult<PyMapFile> {
+    if let Ok(text) = path_or_str.extract::<&str>() {
+        if text.contains('\n') {
+            return Ok(PyMapFile::from(&MapFile::parse(text)?));
+        }
+    }
+
+    let path: PathBuf = path_or_str.extract()?;
+    let map_data = std::fs::read(path)?;
+    let map = MapFileOwned::load_bytes(&map_data)?;
+
+    Ok(PyMapFile::from(&map))
+}
+
+/// the `mapparse` python module. the crate has no `cdylib` crate type, so build it with
+/// `cargo rustc --lib --crate-type cdylib --features python` (or maturin, which does the same)
+#[pymodule]
+fn mapparse(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(parse, m)?)?;
+    m.add_class::<PyMapFile>()?;
+    m.add_class::<PySection>()?;
+    m.add_class::<PySymbol>()?;
+
+    Ok(())
+}
+
+#[test]
+fn parse_from_python() {
+    Python::initialize();
+
+    Python::attach(|py| {
+        let map = parse(&"fixtures/aliases.map".into_pyobject(py).unwrap()).unwrap();
+        assert_eq!(map.file_name, "aliases");
+        assert_eq!(map.functions.len(), 6);
+        assert_eq!(map.functions[0].va, 0x10001000);
+        assert_eq!(map.sections[0].class, "CODE");
+        assert_eq!(map.static_symbols[0].name, "_local_helper");
+
+        let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+        let from_text = parse(&map_string.into_pyobject(py).unwrap()).unwrap();
+        assert_eq!(from_text.functions.len(), map.functions.len());
+
+        let error = parse(&" aliases\n".into_pyobject(py).unwrap())
+            .err()
+            .unwrap();
+        assert!(error.is_instance_of::<PyValueError>(py));
+    });
+}
@@ -0,0 +1,939 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::ops::Range;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+#[cfg(feature = "std")]
+use msvc_demangler::DemangleFlags;
+
+use crate::{
+    Address, Class, Export, Function, LibObject, MapFile, Section, Symbol, SymbolFlags, SymbolRef,
+};
+
+impl<'a> MapFile<'a> {
+    /// the section contribution `addr` falls into
+    pub(crate) fn section_containing(&self, addr: &Address) -> Option<&Section<'a>> {
+        self.sections.iter().find(|section| section.contains(addr))
+    }
+
+    /// the class of the section `addr` is in, `None` if it is in no section at all
+    pub fn class_of(&self, addr: &Address) -> Option<Class<'a>> {
+        self.section_containing(addr).map(|x| x.class.clone())
+    }
+
+    /// segment -> the offsets its sections span, from the lowest start to the highest end.
+    /// goes with [`MapFile::sections_by_address`] for drawing a memory map
+    pub fn segment_layout(&self) -> BTreeMap<u32, Range<usize>> {
+        let mut layout: BTreeMap<u32, Range<usize>> = BTreeMap::new();
+
+        for section in &self.sections {
+            layout
+                .entry(section.addr.seg)
+                .and_modify(|x| {
+                    x.start = x.start.min(section.addr.addr);
+                    x.end = x.end.max(section.end());
+                })
+                .or_insert(section.addr.addr..section.end());
+        }
+
+        layout
+    }
+
+    /// the rva segment `seg` starts at, i.e. what turns a `seg:offset` address into an rva.
+    ///
+    /// link.exe maps don't list segment bases, so it is worked out from a placed symbol in the
+    /// segment. a segment without any (e.g. one holding only `.bss` or resources) has no known
+    /// base. scans the symbol tables, so cache the result for lots of lookups
+    pub fn segment_rva(&self, seg: u32) -> Option<usize> {
+        self.symbols()
+            .find(|x| x.addr().seg == seg && x.rva() != 0)
+            .and_then(|x| x.rva().checked_sub(x.addr().addr))
+    }
+
+    /// every symbol that sits in a section, paired with an approximate size.
+    ///
+    /// the map doesn't record sizes, so a symbol is assumed to run up to the next symbol at a
+    /// higher address or the end of its section, whichever comes first. symbols sharing an
+    /// address get the same size
+    #[cfg(any(feature = "std", feature = "interval-tree"))]
+    pub(crate) fn inferred_sizes(&self) -> Vec<(SymbolRef<'_>, usize)> {
+        let symbols: Vec<SymbolRef> = self.symbols_by_rva().collect();
+
+        let mut sizes = Vec::with_capacity(symbols.len());
+
+        for (idx, symbol) in symbols.iter().enumerate() {
+            let section = match self.section_containing(symbol.addr()) {
+                Some(section) => section,
+                None => continue,
+            };
+
+            let section_end = symbol.rva() + (section.end() - symbol.addr().addr);
+
+            let next = symbols[idx + 1..]
+                .iter()
+                .map(|x| x.rva())
+                .find(|&x| x > symbol.rva())
+                .unwrap_or(section_end);
+
+            sizes.push((*symbol, next.min(section_end) - symbol.rva()));
+        }
+
+        sizes
+    }
+
+    /// approximate size of `functions[idx]`: the distance to the next public at a higher
+    /// address, or to the end of its section if that comes first. statics don't cut a public
+    /// short. `None` if there is no such public or it sits in no section
+    pub fn function_size(&self, idx: usize) -> Option<usize> {
+        let function = self.functions.get(idx)?;
+        let section = self.section_containing(&function.addr)?;
+
+        let section_end = function.rva.0 + (section.end() - function.addr.addr);
+
+        // publics are sorted by rva, so the next higher one is the first one after the aliases
+        let next = self.functions[idx + 1..]
+            .iter()
+            .map(|x| x.rva.0)
+            .find(|&x| x > function.rva.0)
+            .unwrap_or(section_end);
+
+        Some(next.min(section_end) - function.rva.0)
+    }
+
+    /// every public in a section along with [`MapFile::function_size`], e.g. for defining
+    /// functions with their extents in a disassembler
+    pub fn function_sizes(&self) -> impl Iterator<Item = (&Function<'a>, usize)> {
+        self.functions
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, function)| Some((function, self.function_size(idx)?)))
+    }
+
+    /// publics that identical COMDAT folding (`/OPT:ICF`) or aliasing put at the same address,
+    /// in map order within each group. publics with a zero rva aren't placed, so they never fold
+    pub fn folded_groups(&self) -> Vec<Vec<&Function<'a>>> {
+        self.functions
+            .chunk_by(|a, b| a.rva.0 == b.rva.0)
+            .filter(|x| x.len() > 1 && x[0].rva.0 != 0)
+            .map(|x| x.iter().collect())
+            .collect()
+    }
+
+    /// sections in the order they are laid out in the image, e.g. for rendering a memory map or
+    /// spotting gaps between them. link.exe numbers segments in image order, so this is the same
+    /// as sorting on the section rva
+    pub fn sections_by_address(&self) -> Vec<&Section<'a>> {
+        let mut sections: Vec<&Section<'a>> = self.sections.iter().collect();
+        sections.sort_by_key(|x| (x.addr.seg, x.addr.addr));
+        sections
+    }
+
+    /// the dll's exports in ordinal order, as opposed to every public including internal ones
+    pub fn export_api_symbols(&self) -> Vec<&Export<'a>> {
+        let mut exports: Vec<&Export<'a>> = self.exports.iter().collect();
+        exports.sort_by_key(|x| x.ordinal);
+        exports
+    }
+
+    /// the export published under `ordinal`, for resolving imports by ordinal
+    pub fn find_by_ordinal(&self, ordinal: u16) -> Option<&Export<'a>> {
+        self.exports.iter().find(|x| x.ordinal == ordinal)
+    }
+
+    /// the public whose range contains `rva`, for symbolicating crash addresses.
+    ///
+    /// publics have no size, so each one is taken to run up to the next public or the end of its
+    /// section. an address between two publics resolves to the preceding one, while one past the
+    /// end of the preceding public's section resolves to nothing. of several publics sharing an
+    /// address, the first one is returned
+    pub fn symbol_at(&self, rva: usize) -> Option<&Function<'a>> {
+        let idx = self.functions.partition_point(|x| x.rva.0 <= rva);
+        let function = self.functions[..idx].last()?;
+
+        let first = self.functions[..idx]
+            .iter()
+            .rposition(|x| x.rva.0 != function.rva.0)
+            .map_or(0, |x| x + 1);
+        let function = &self.functions[first];
+
+        let section = self.section_containing(&function.addr)?;
+        let section_end = function.rva.0 + (section.end() - function.addr.addr);
+
+        (rva < section_end).then_some(function)
+    }
+
+    /// [`MapFile::symbol_at`] along with how far into the public `rva` is, for `func+0x1a`
+    /// style output
+    pub fn offset_into_symbol(&self, rva: usize) -> Option<(&Function<'a>, usize)> {
+        self.symbol_at(rva)
+            .map(|function| (function, rva - function.rva.0))
+    }
+
+    /// the public or static symbol covering the absolute address `va`, e.g. one read off a
+    /// debugger. `actual_base` is where the image really got loaded, `None` for the preferred
+    /// load address. like [`MapFile::symbol_at`], a symbol runs up to the next one or the end
+    /// of its section, and where symbols share an rva the first public wins. lld-link
+    /// `/lldmap` maps have no preferred load address, so for those `actual_base` has to be
+    /// given or `va` is taken as an rva
+    pub fn symbol_at_va(&self, va: usize, actual_base: Option<usize>) -> Option<SymbolRef<'_>> {
+        let rva = va.checked_sub(actual_base.unwrap_or(self.preferred_load_addr))?;
+        // the closest rva at or below the one asked for
+        let start = self.prev_symbol(rva.saturating_add(1))?.rva();
+
+        // prev_symbol hands out the last symbol at that rva, statics after publics
+        let public = &self.functions[self.functions.partition_point(|x| x.rva.0 < start)..];
+        let static_ =
+            &self.static_symbols[self.static_symbols.partition_point(|x| x.rva.0 < start)..];
+        let symbol = match (public.first(), static_.first()) {
+            (Some(public), _) if public.rva.0 == start => SymbolRef::Function(public),
+            (_, Some(static_)) => SymbolRef::Static(static_),
+            (public, None) => SymbolRef::Function(public?),
+        };
+
+        let section = self.section_containing(symbol.addr())?;
+        let section_end = symbol.rva() + (section.end() - symbol.addr().addr);
+
+        (rva < section_end).then_some(symbol)
+    }
+
+    /// the public or, failing that, static symbol with exactly this mangled name. this is a
+    /// linear scan, see [`MapFile::name_index`] for looking up lots of names
+    pub fn symbol_named(&self, name: &str) -> Option<SymbolRef<'_>> {
+        self.symbols().find(|x| x.symbol() == name)
+    }
+
+    /// like [`MapFile::symbol_named`], but matching `name` against the name-only demangled
+    /// form, e.g. `Foo::Release`. plain C names match as they are
+    #[cfg(feature = "std")]
+    pub fn symbol_named_demangled(&self, name: &str) -> Option<SymbolRef<'_>> {
+        self.symbols().find(|x| {
+            match msvc_demangler::demangle(x.symbol(), DemangleFlags::NAME_ONLY) {
+                Ok(demangled) => demangled == name,
+                Err(_) => x.symbol() == name,
+            }
+        })
+    }
+
+    /// where the symbol with this mangled name lives, see [`MapFile::symbol_named`]
+    pub fn address_of(&self, name: &str) -> Option<&Address> {
+        self.symbol_named(name).map(|x| x.addr())
+    }
+
+    /// the rva of the symbol with this mangled name, see [`MapFile::symbol_named`]
+    pub fn rva_of(&self, name: &str) -> Option<usize> {
+        self.symbol_named(name).map(|x| x.rva())
+    }
+
+    /// the import address table entries, see [`Symbol::is_import`]. link.exe has no flag for
+    /// imports (its `i` is [`SymbolFlags::IS_INLINE`]), so they are told apart by the `__imp_`
+    /// prefix the linker gives every slot
+    pub fn imports(&self) -> impl Iterator<Item = &Function<'a>> {
+        self.functions.iter().filter(|x| x.is_import())
+    }
+
+    /// publics defined in this image: everything but imports and `<absolute>` symbols
+    pub fn locals(&self) -> impl Iterator<Item = &Function<'a>> {
+        self.functions
+            .iter()
+            .filter(|x| !x.is_import() && !matches!(x.libobj, LibObject::Absolute))
+    }
+
+    /// publics that aren't thunks, see [`Symbol::is_thunk`]
+    pub fn non_thunks(&self) -> impl Iterator<Item = &Function<'a>> {
+        self.non_thunks_with(DEFAULT_THUNK_PREFIXES)
+    }
+
+    /// [`MapFile::non_thunks`] with a custom set of name prefixes
+    pub fn non_thunks_with<'m>(
+        &'m self,
+        prefixes: &'m [&str],
+    ) -> impl Iterator<Item = &'m Function<'a>> {
+        self.functions.iter().filter(|x| !x.is_thunk_with(prefixes))
+    }
+
+    /// publics with a decorated C++ name, i.e. ones that demangle to something other than
+    /// themselves. plain C symbols like `_lj_BC_ISLT` are left out
+    #[cfg(feature = "std")]
+    pub fn cpp_symbols(&self) -> Vec<&Function<'a>> {
+        self.functions
+            .iter()
+            .filter(|x| {
+                msvc_demangler::demangle(&x.symbol, DemangleFlags::NAME_ONLY)
+                    .is_ok_and(|demangled| demangled != x.symbol)
+            })
+            .collect()
+    }
+
+    /// publics bucketed by the object file they came from, ready for a per-object report.
+    /// buckets are `(lib, obj, symbols)`, keyed on the lib as well as the object like
+    /// [`MapFile::by_object`], so same-named objects from different libs stay apart. groups
+    /// are sorted by object name, then lib, and symbols by rva; `<absolute>` symbols are left out
+    pub fn symbols_grouped_by_object_sorted(
+        &self,
+    ) -> Vec<(Option<&str>, &str, Vec<&Function<'a>>)> {
+        let mut groups: BTreeMap<(&str, Option<&str>), Vec<&Function<'a>>> = BTreeMap::new();
+
+        for function in &self.functions {
+            if let LibObject::LibObj(lib, obj) = &function.libobj {
+                groups
+                    .entry((obj, lib.as_deref()))
+                    .or_default()
+                    .push(function);
+            }
+        }
+
+        groups
+            .into_iter()
+            .map(|((obj, lib), mut functions)| {
+                functions.sort_by_key(|x| x.rva.0);
+                (lib, obj, functions)
+            })
+            .collect()
+    }
+
+    /// publics bucketed by `(lib, obj)`, the lib being `None` for objects linked in directly.
+    /// `<absolute>` symbols are left out. symbols keep their map order within a bucket
+    #[cfg(feature = "std")]
+    pub fn by_object(&self) -> HashMap<(Option<&str>, &str), Vec<&Function<'a>>> {
+        let mut groups: HashMap<(Option<&str>, &str), Vec<&Function<'a>>> = HashMap::new();
+
+        for function in &self.functions {
+            if let LibObject::LibObj(lib, obj) = &function.libobj {
+                groups
+                    .entry((lib.as_deref(), obj))
+                    .or_default()
+                    .push(function);
+            }
+        }
+
+        groups
+    }
+
+    /// every symbol, public or static, that lives in a section of the given class,
+    /// in address order
+    pub fn symbols_matching_section_class(&self, class: Class) -> Vec<SymbolRef<'_>> {
+        self.symbols_by_rva()
+            .filter(|x| self.class_of(x.addr()).as_ref() == Some(&class))
+            .collect()
+    }
+
+    /// fraction of code section bytes that are covered by a named function.
+    ///
+    /// symbols have no size in the map, so each one is assumed to extend up to the next symbol
+    /// or the end of its section. only symbols flagged as functions count: bytes in front of a
+    /// section's first symbol, behind data that lives in a code section (jump tables, string
+    /// literals) and whole sections without any symbol are unnamed
+    pub fn named_code_coverage(&self) -> f32 {
+        let mut total = 0usize;
+        let mut named = 0usize;
+
+        for section in self
+            .sections
+            .iter()
+            .filter(|x| matches!(x.class, Class::Code))
+        {
+            total += section.len;
+
+            let mut starts: Vec<(usize, bool)> = self
+                .symbols()
+                .filter(|x| section.contains(x.addr()))
+                .map(|x| (x.addr().addr, x.flags().contains(SymbolFlags::IS_FUNCTION)))
+                .collect();
+            // symbols sharing an offset (aliases) cover it as a function if any of them is one
+            starts.sort();
+            starts.dedup_by(|next, prev| {
+                let same = next.0 == prev.0;
+                if same {
+                    prev.1 |= next.1;
+                }
+                same
+            });
+
+            for (idx, &(start, is_function)) in starts.iter().enumerate() {
+                let end = starts.get(idx + 1).map_or(section.end(), |x| x.0);
+
+                if is_function {
+                    named += end - start;
+                }
+            }
+        }
+
+        match total {
+            0 => 0.0,
+            _ => named as f32 / total as f32,
+        }
+    }
+}
+
+/// the image section a contribution like `.text$mn` is merged into
+fn image_section_name<'m>(section: &'m Section) -> &'m str {
+    match section.name.find('$') {
+        Some(idx) => &section.name[..idx],
+        None => &section.name,
+    }
+}
+
+/// name prefixes [`Symbol::is_thunk`] looks for: import address table slots, incremental
+/// linking thunks and delay-load helpers
+pub const DEFAULT_THUNK_PREFIXES: &[&str] = &["__imp_", "@ILT+", "__tailMerge_"];
+
+impl<'a> Symbol<'a> {
+    /// whether this is an import address table slot (`__imp_`), pointing at code in another
+    /// module rather than being code itself. link.exe's `i` flag means something else, see
+    /// [`SymbolFlags::IS_INLINE`](crate::SymbolFlags::IS_INLINE)
+    pub fn is_import(&self) -> bool {
+        self.symbol.starts_with("__imp_")
+    }
+
+    /// whether this looks like a thunk or trampoline rather than a real function, going by
+    /// [`DEFAULT_THUNK_PREFIXES`]. these often share a name with the function they jump to
+    pub fn is_thunk(&self) -> bool {
+        self.is_thunk_with(DEFAULT_THUNK_PREFIXES)
+    }
+
+    /// [`Symbol::is_thunk`] with a custom set of name prefixes
+    pub fn is_thunk_with(&self, prefixes: &[&str]) -> bool {
+        prefixes.iter().any(|x| self.symbol.starts_with(x))
+    }
+
+    /// name of the image section (`.text`, `.data`, ...) this symbol lives in
+    pub fn section_name<'m>(&self, map: &'m MapFile) -> Option<&'m str> {
+        map.section_containing(&self.addr).map(image_section_name)
+    }
+
+    /// an id for following this function across builds. it only depends on the demangled name
+    /// and the object the function came from, not on where it was placed.
+    ///
+    /// uses fnv-1a so the id stays the same across runs and compiler versions
+    #[cfg(feature = "std")]
+    pub fn stable_id(&self) -> u64 {
+        let name = msvc_demangler::demangle(&self.symbol, DemangleFlags::COMPLETE)
+            .unwrap_or(self.symbol.to_string());
+
+        let (lib, obj) = match &self.libobj {
+            LibObject::LibObj(lib, obj) => (lib.as_deref().unwrap_or_default(), &**obj),
+            LibObject::Absolute => ("", "<absolute>"),
+        };
+
+        [name.as_str(), lib, obj]
+            .iter()
+            .flat_map(|x| x.bytes().chain([0]))
+            .fold(0xcbf29ce484222325, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+            })
+    }
+}
+
+#[test]
+fn named_code_coverage() {
+    let map_string = std::fs::read_to_string("fixtures/code-gap.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    // .text is unnamed up to 0x400, .text$yd is fully named: 0x1000 of 0x1400 bytes
+    assert_eq!(map.named_code_coverage(), 0.8);
+
+    let map_string = std::fs::read_to_string("fixtures/code-data.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    // .text$mn is unnamed up to 0x200 and behind the jump table from 0x400 to 0x800,
+    // .text$x has no symbols at all: 0xa00 of 0x2000 bytes
+    assert_eq!(map.named_code_coverage(), 0.3125);
+}
+
+#[test]
+fn section_name() {
+    let map_string = std::fs::read_to_string("fixtures/mapinfo-exports.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let function = map
+        .functions
+        .iter()
+        .find(|x| x.symbol == "_DllMain@12")
+        .unwrap();
+    assert_eq!(function.section_name(&map), Some(".text"));
+
+    let data = map
+        .functions
+        .iter()
+        .find(|x| x.symbol == "_g_counter")
+        .unwrap();
+    assert_eq!(data.section_name(&map), Some(".data"));
+
+    let absolute = &map.functions[0];
+    assert_eq!(absolute.section_name(&map), None);
+}
+
+#[test]
+fn section_name_strips_contribution_suffix() {
+    let map_data = std::fs::read("csgo-x86.map").unwrap();
+    let map_string = String::from_utf8(map_data).unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    // lives in the .text$mn contribution
+    let function = map
+        .functions
+        .iter()
+        .find(|x| x.symbol == "??0LegitAimbot@impl@mod@csgo@aw@@QAE@XZ")
+        .unwrap();
+    assert_eq!(function.section_name(&map), Some(".text"));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn cpp_symbols() {
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let cpp: Vec<&str> = map.cpp_symbols().iter().map(|x| &*x.symbol).collect();
+
+    assert_eq!(cpp, ["?Release@Foo@@UAEXXZ", "?Release@Bar@@UAEXXZ"]);
+}
+
+#[test]
+fn symbols_grouped_by_object_sorted() {
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let groups: Vec<(&str, Vec<&str>)> = map
+        .symbols_grouped_by_object_sorted()
+        .into_iter()
+        .map(|(_, obj, functions)| (obj, functions.iter().map(|x| &*x.symbol).collect()))
+        .collect();
+
+    assert_eq!(
+        groups,
+        [
+            ("bar.obj", vec!["?Release@Bar@@UAEXXZ"]),
+            ("foo.obj", vec!["?Release@Foo@@UAEXXZ"]),
+            (
+                "main.obj",
+                vec!["_init", "_shutdown", "_unwind", "_g_state"]
+            ),
+        ]
+    );
+
+    // three `util.obj`s, one linked in directly and one from each of two libs
+    let map_string = std::fs::read_to_string("fixtures/object-collision.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let groups: Vec<_> = map
+        .symbols_grouped_by_object_sorted()
+        .into_iter()
+        .map(|(lib, obj, functions)| {
+            let symbols: Vec<&str> = functions.iter().map(|x| &*x.symbol).collect();
+            (lib, obj, symbols)
+        })
+        .collect();
+
+    assert_eq!(
+        groups,
+        [
+            (None, "util.obj", vec!["_main"]),
+            (Some("gfx.lib"), "util.obj", vec!["_gfx_util_init"]),
+            (
+                Some("net.lib"),
+                "util.obj",
+                vec!["_net_util_init", "_net_util_send"]
+            ),
+        ]
+    );
+}
+
+#[test]
+fn symbols_matching_section_class() {
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let code: Vec<&str> = map
+        .symbols_matching_section_class(Class::Code)
+        .iter()
+        .map(|x| x.symbol())
+        .collect();
+
+    assert_eq!(
+        code,
+        [
+            "_init",
+            "?Release@Foo@@UAEXXZ",
+            "?Release@Bar@@UAEXXZ",
+            "_local_helper",
+            "_shutdown",
+            "_unwind"
+        ]
+    );
+
+    let data: Vec<&str> = map
+        .symbols_matching_section_class(Class::Data)
+        .iter()
+        .map(|x| x.symbol())
+        .collect();
+
+    assert_eq!(data, ["_g_state"]);
+}
+
+#[test]
+fn sections_by_address() {
+    let map_data = std::fs::read("csgo-x86.map").unwrap();
+    let map_string = String::from_utf8(map_data).unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let sections = map.sections_by_address();
+    assert_eq!(sections.len(), map.sections.len());
+    assert!(sections
+        .windows(2)
+        .all(|x| (x[0].addr.seg, x[0].addr.addr) <= (x[1].addr.seg, x[1].addr.addr)));
+    assert_eq!(sections[0].name, ".text");
+}
+
+#[test]
+fn export_api_symbols() {
+    let map_string = std::fs::read_to_string("fixtures/mapinfo-exports.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let names: Vec<&str> = map
+        .export_api_symbols()
+        .iter()
+        .map(|x| x.exported_name())
+        .collect();
+    assert_eq!(names, ["CreateInterface", "?helper@@YAXXZ"]);
+    assert!(!names.contains(&"_DllMain@12"));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn stable_id() {
+    let map_string = std::fs::read_to_string("fixtures/mapinfo-exports.map").unwrap();
+    let map = MapFile::load(&map_string).unwrap();
+
+    let rebuilt_string = std::fs::read_to_string("fixtures/rebuilt.map").unwrap();
+    let rebuilt = MapFile::load(&rebuilt_string).unwrap();
+
+    let find = |map: &MapFile<'_>, symbol: &str| {
+        map.functions
+            .iter()
+            .find(|x| x.symbol == symbol)
+            .unwrap()
+            .stable_id()
+    };
+
+    // moved from 0x1100 to 0x1200
+    assert_eq!(
+        find(&map, "?helper@@YAXXZ"),
+        find(&rebuilt, "?helper@@YAXXZ")
+    );
+    assert_eq!(
+        find(&map, "_CreateInterface@8"),
+        find(&rebuilt, "_CreateInterface@8")
+    );
+    assert_ne!(
+        find(&map, "?helper@@YAXXZ"),
+        find(&map, "_CreateInterface@8")
+    );
+}
+
+#[test]
+fn find_by_ordinal() {
+    let map_string = std::fs::read_to_string("fixtures/mapinfo-exports.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let export = map.find_by_ordinal(1).unwrap();
+    assert_eq!(export.exported_name(), "CreateInterface");
+    assert_eq!(export.resolve(&map).unwrap().rva.0, 0x1040);
+    assert!(map.find_by_ordinal(3).is_none());
+
+    // no exports section at all
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+    let map = MapFile::load(&map_string).unwrap();
+    assert!(map.find_by_ordinal(1).is_none());
+}
+
+#[test]
+fn symbol_at() {
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    assert!(map.symbol_at(0xfff).is_none());
+    assert_eq!(map.symbol_at(0x1000).unwrap().symbol, "_init");
+    // first of the folded aliases
+    assert_eq!(
+        map.symbol_at(0x1040).unwrap().symbol,
+        "?Release@Foo@@UAEXXZ"
+    );
+    assert_eq!(map.symbol_at(0x10ff).unwrap().symbol, "_shutdown");
+    assert_eq!(map.symbol_at(0x11ff).unwrap().symbol, "_unwind");
+    // past the end of .text$x, in no section
+    assert!(map.symbol_at(0x1200).is_none());
+
+    let (function, offset) = map.offset_into_symbol(0x105a).unwrap();
+    assert_eq!(function.symbol, "?Release@Foo@@UAEXXZ");
+    assert_eq!(offset, 0x1a);
+}
+
+#[test]
+fn symbol_at_va() {
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    assert!(map.symbol_at_va(0x1000, None).is_none());
+    assert!(map.symbol_at_va(0x10000fff, None).is_none());
+    assert_eq!(
+        map.symbol_at_va(0x10001004, None).unwrap().symbol(),
+        "_init"
+    );
+    // statics count too
+    let symbol = map.symbol_at_va(0x10001070, None).unwrap();
+    assert!(matches!(symbol, SymbolRef::Static(_)));
+    assert_eq!(symbol.symbol(), "_local_helper");
+    assert!(map.symbol_at_va(0x10001200, None).is_none());
+
+    // rebased
+    assert_eq!(
+        map.symbol_at_va(0x6a001084, Some(0x6a000000))
+            .unwrap()
+            .symbol(),
+        "_shutdown"
+    );
+    assert!(map.symbol_at_va(0x10001084, Some(0x6a000000)).is_none());
+
+    // a static at a public's rva loses to the public, like it does in symbol_at
+    let map_string = map_string.replace(
+        "0001:00000060       _local_helper              10001060",
+        "0001:00000080       _local_helper              10001080",
+    );
+    let map = MapFile::load(&map_string).unwrap();
+    let symbol = map.symbol_at_va(0x10001084, None).unwrap();
+    assert!(matches!(symbol, SymbolRef::Function(_)));
+    assert_eq!(symbol.symbol(), "_shutdown");
+    assert_eq!(map.symbol_at(0x1084).unwrap().symbol, "_shutdown");
+}
+
+#[test]
+fn class_of() {
+    let map_string = std::fs::read_to_string("fixtures/mapinfo-exports.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let class_of = |symbol: &str| {
+        let function = map.functions.iter().find(|x| x.symbol == symbol).unwrap();
+        map.class_of(&function.addr)
+    };
+
+    assert_eq!(class_of("_DllMain@12"), Some(Class::Code));
+    assert_eq!(class_of("_g_counter"), Some(Class::Data));
+    assert_eq!(class_of("___safe_se_handler_count"), None);
+
+    // one past the end of .data
+    assert_eq!(
+        map.class_of(&Address {
+            seg: 3,
+            addr: 0x100
+        }),
+        None
+    );
+}
+
+#[test]
+fn imports() {
+    let map_data = std::fs::read("csgo-x86.map").unwrap();
+    let map_string = String::from_utf8(map_data).unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let imports: Vec<&Function> = map.imports().collect();
+    assert!(!imports.is_empty());
+    assert!(imports.iter().any(|x| x.symbol == "__imp__SetTextColor@8"));
+    assert!(imports.iter().all(|x| matches!(
+        &x.libobj,
+        LibObject::LibObj(Some(_), obj) if obj.to_lowercase().ends_with(".dll")
+    )));
+
+    let absolute = map
+        .functions
+        .iter()
+        .filter(|x| matches!(x.libobj, LibObject::Absolute))
+        .count();
+    assert_eq!(
+        map.locals().count() + imports.len() + absolute,
+        map.functions.len()
+    );
+    assert!(map.locals().all(|x| !x.symbol.starts_with("__imp_")));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn by_object() {
+    let map_data = std::fs::read("csgo-x86.map").unwrap();
+    let map_string = String::from_utf8(map_data).unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let groups = map.by_object();
+
+    let lib_bit = &groups[&(Some("luajit-x86"), "lib_bit.obj")];
+    assert!(!lib_bit.is_empty());
+    assert!(lib_bit.iter().all(|x| matches!(
+        &x.libobj,
+        LibObject::LibObj(Some(lib), obj) if lib == "luajit-x86" && obj == "lib_bit.obj"
+    )));
+
+    assert!(groups.keys().any(|(lib, _)| lib.is_none()));
+
+    let absolute = map
+        .functions
+        .iter()
+        .filter(|x| matches!(x.libobj, LibObject::Absolute))
+        .count();
+    assert_eq!(
+        groups.values().map(Vec::len).sum::<usize>() + absolute,
+        map.functions.len()
+    );
+}
+
+#[test]
+fn function_size() {
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let sizes: Vec<(&str, usize)> = map
+        .function_sizes()
+        .map(|(function, size)| (&*function.symbol, size))
+        .collect();
+
+    assert_eq!(
+        sizes,
+        [
+            ("_init", 0x40),
+            // the static in between doesn't count
+            ("?Release@Foo@@UAEXXZ", 0x40),
+            ("?Release@Bar@@UAEXXZ", 0x40),
+            // bounded by the end of .text$mn, not by _unwind
+            ("_shutdown", 0x80),
+            ("_unwind", 0x100),
+            ("_g_state", 0xf0),
+        ]
+    );
+
+    assert_eq!(map.function_size(3), Some(0x80));
+    assert_eq!(map.function_size(6), None);
+}
+
+#[test]
+fn folded_groups() {
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let groups: Vec<Vec<&str>> = map
+        .folded_groups()
+        .iter()
+        .map(|x| x.iter().map(|x| &*x.symbol).collect())
+        .collect();
+    assert_eq!(groups, [["?Release@Foo@@UAEXXZ", "?Release@Bar@@UAEXXZ"]]);
+
+    // unplaced symbols share rva 0 without being folded
+    let map_string = std::fs::read_to_string("fixtures/unplaced.map").unwrap();
+    let map = MapFile::load(&map_string).unwrap();
+    assert!(map.folded_groups().is_empty());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn address_of() {
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    assert_eq!(map.rva_of("_shutdown"), Some(0x1080));
+    assert_eq!(
+        map.address_of("?Release@Bar@@UAEXXZ"),
+        Some(&Address { seg: 1, addr: 0x40 })
+    );
+    // statics too
+    assert_eq!(map.rva_of("_local_helper"), Some(0x1060));
+    assert_eq!(map.rva_of("_missing"), None);
+
+    assert_eq!(
+        map.symbol_named_demangled("Bar::Release").unwrap().symbol(),
+        "?Release@Bar@@UAEXXZ"
+    );
+    assert_eq!(map.symbol_named_demangled("_init").unwrap().rva(), 0x1000);
+}
+
+#[test]
+fn segment_layout() {
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+    // data ahead of code, and the second code section ahead of the first
+    let map_string = map_string.replace(
+        " 0001:00000000 00000100H .text$mn                CODE\n 0001:00000100 00000100H .text$x                 CODE\n 0002:00000000 00000100H .data                   DATA\n",
+        " 0002:00000000 00000100H .data                   DATA\n 0001:00000100 00000100H .text$x                 CODE\n 0001:00000000 00000100H .text$mn                CODE\n",
+    );
+
+    let map = MapFile::load(&map_string).unwrap();
+    assert_eq!(map.sections[0].name, ".data");
+
+    let names: Vec<&str> = map.sections_by_address().iter().map(|x| &*x.name).collect();
+    assert_eq!(names, [".text$mn", ".text$x", ".data"]);
+
+    assert_eq!(
+        map.segment_layout().into_iter().collect::<Vec<_>>(),
+        [(1, 0..0x200), (2, 0..0x100)]
+    );
+}
+
+#[test]
+fn section_rva() {
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+
+    let mut map = MapFile::load(&map_string).unwrap();
+
+    assert_eq!(map.segment_rva(1), Some(0x1000));
+    assert_eq!(map.sections[1].rva(&map), Some(0x1100));
+    assert_eq!(map.sections[2].rva(&map), Some(0x3000));
+
+    // nothing is placed in the segment to derive its base from
+    map.functions.retain(|x| x.addr.seg != 2);
+    assert_eq!(map.sections[2].rva(&map), None);
+}
+
+#[test]
+fn non_thunks() {
+    let map_string = std::fs::read_to_string("fixtures/aliases.map")
+        .unwrap()
+        .replace("_shutdown       ", "__imp__shutdown ")
+        .replace("_unwind         ", "@ILT+0(_unwind) ");
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    assert!(map.functions[3].is_thunk());
+    assert!(map.functions[4].is_thunk());
+
+    let names: Vec<&str> = map.non_thunks().map(|x| &*x.symbol).collect();
+    assert_eq!(
+        names,
+        [
+            "_init",
+            "?Release@Foo@@UAEXXZ",
+            "?Release@Bar@@UAEXXZ",
+            "_g_state"
+        ]
+    );
+
+    // only count incremental linking thunks
+    assert_eq!(map.non_thunks_with(&["@ILT+"]).count(), 5);
+    assert!(!map.functions[3].is_thunk_with(&["@ILT+"]));
+}
@@ -0,0 +1,283 @@
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::io::BufRead;
+
+use anyhow::Context;
+
+use crate::owned::owned;
+use crate::{
+    Address, EncodingInfo, Export, MapFileOwned, MapParseError, ParseError, ParseOptions, Parser,
+    PublicsLayout, Section, Stage, StaticSymbol, Symbol, MODULE_NAME_DISTANCE,
+};
+
+/// the parser state that carries over from one line to the next, with everything already
+/// detached from the line it came from
+#[derive(Default)]
+struct StreamParser {
+    options: ParseOptions,
+    stage: Stage,
+    filename: Option<Cow<'static, str>>,
+    timestamp: Option<Cow<'static, str>>,
+    timestamp_raw: Option<u32>,
+    load_address: Option<usize>,
+    entry_point: Option<Address>,
+    publics_layout: PublicsLayout,
+    sections: Vec<Section<'static>>,
+    functions: Vec<Symbol<'static>>,
+    static_symbols: Vec<StaticSymbol<'static>>,
+    exports: Vec<Export<'static>>,
+}
+
+impl StreamParser {
+    /// runs one line through a [`Parser`] borrowing from it, then takes ownership of whatever
+    /// came out of it
+    fn parse_line(&mut self, line: usize, line_start: usize, data: &str) -> Result<(), ParseError> {
+        let mut parser = Parser {
+            options: self.options,
+            stage: std::mem::take(&mut self.stage),
+            filename: self.filename.take(),
+            timestamp: self.timestamp.take(),
+            timestamp_raw: self.timestamp_raw,
+            load_address: self.load_address,
+            entry_point: self.entry_point.take(),
+            publics_layout: self.publics_layout,
+            // `exported name:` lines amend the export in front of them
+            exports: self.exports.pop().into_iter().collect(),
+            token: (0, data.len()),
+            line,
+            ..Default::default()
+        };
+
+        parser.parse_line(data).map_err(|error| {
+            let (start, len) = parser.token;
+            let error = MapParseError {
+                line,
+                byte_offset: line_start + start,
+                len,
+                error,
+            };
+
+            ParseError::with_token(error, data.get(start..start + len).unwrap_or_default())
+        })?;
+
+        self.stage = parser.stage;
+        self.filename = parser.filename.map(owned);
+        self.timestamp = parser.timestamp.map(owned);
+        self.timestamp_raw = parser.timestamp_raw;
+        self.load_address = parser.load_address;
+        self.entry_point = parser.entry_point;
+        self.publics_layout = parser.publics_layout;
+
+        self.sections
+            .extend(parser.sections.into_iter().map(Section::into_owned));
+        self.functions
+            .extend(parser.functions.into_iter().map(Symbol::into_owned));
+        self.static_symbols
+            .extend(parser.static_symbols.into_iter().map(Symbol::into_owned));
+        self.exports
+            .extend(parser.exports.into_iter().map(Export::into_owned));
+
+        Ok(())
+    }
+
+    fn finish(self, line: usize, byte_offset: usize) -> Result<MapFileOwned, ParseError> {
+        Parser {
+            options: self.options,
+            stage: self.stage,
+            filename: self.filename,
+            timestamp: self.timestamp,
+            timestamp_raw: self.timestamp_raw,
+            load_address: self.load_address,
+            entry_point: self.entry_point,
+            sections: self.sections,
+            functions: self.functions,
+            static_symbols: self.static_symbols,
+            exports: self.exports,
+            publics_layout: self.publics_layout,
+            ..Default::default()
+        }
+        .build()
+        .map_err(|error| {
+            let error = MapParseError {
+                line,
+                byte_offset,
+                len: 0,
+                error,
+            };
+
+            ParseError::with_token(error, "")
+        })
+    }
+}
+
+impl MapFileOwned {
+    /// parses a `link.exe` map line by line from `reader`, so only one line of the input is in
+    /// memory at a time. invalid utf-8 is replaced like [`MapFileOwned::load_bytes`] does.
+    ///
+    /// GNU ld and lld-link `/lldmap` maps aren't supported here, load those with
+    /// [`MapFile::load`]
+    ///
+    /// [`MapFile::load`]: crate::MapFile::load
+    pub fn from_reader<R: BufRead>(mut reader: R) -> Result<Self, ParseError> {
+        let mut parser = StreamParser::default();
+        let mut encoding = EncodingInfo::default();
+
+        // lines seen before the `Timestamp is` line, one of which is the module name
+        let mut preamble: VecDeque<(usize, usize, String)> = VecDeque::new();
+        let mut found_header = false;
+
+        let mut buf = Vec::new();
+        let mut line = 0;
+        let mut offset = 0;
+
+        loop {
+            buf.clear();
+
+            let read = reader
+                .read_until(b'\n', &mut buf)
+                .context("unable to read the map")
+                .map_err(|error| MapParseError {
+                    line: line + 1,
+                    byte_offset: offset,
+                    len: 0,
+                    error,
+                })?;
+
+            if read == 0 {
+                break;
+            }
+
+            line += 1;
+            let mut line_start = offset;
+            offset += read;
+
+            let mut bytes = &buf[..];
+
+            if line == 1 {
+                if let Some(rest) = bytes.strip_prefix(b"\xef\xbb\xbf") {
+                    bytes = rest;
+                    line_start += 3;
+                    encoding.had_bom = true;
+                }
+            }
+
+            // the same line endings str::lines strips
+            bytes = bytes.strip_suffix(b"\n").unwrap_or(bytes);
+            bytes = bytes.strip_suffix(b"\r").unwrap_or(bytes);
+
+            encoding.lossy_replacements += bytes
+                .utf8_chunks()
+                .filter(|x| !x.invalid().is_empty())
+                .count();
+
+            let data = String::from_utf8_lossy(bytes);
+
+            if found_header {
+                parser.parse_line(line, line_start, &data)?;
+                continue;
+            }
+
+            if !data.trim_start().starts_with("Timestamp is") {
+                preamble.push_back((line, line_start, data.into_owned()));
+
+                if preamble.len() > MODULE_NAME_DISTANCE {
+                    preamble.pop_front();
+                }
+
+                continue;
+            }
+
+            found_header = true;
+
+            let start = preamble
+                .iter()
+                .rposition(|(_, _, x)| !x.trim().is_empty())
+                .context("no module name found in front of the `Timestamp is` line")
+                .map_err(|error| MapParseError {
+                    line,
+                    byte_offset: line_start,
+                    len: data.len(),
+                    error,
+                })?;
+
+            for (line, line_start, data) in preamble.drain(start..) {
+                parser.parse_line(line, line_start, &data)?;
+            }
+
+            parser.parse_line(line, line_start, &data)?;
+        }
+
+        if !found_header {
+            return Err(MapParseError {
+                line: 1,
+                byte_offset: 0,
+                len: 0,
+                error: anyhow::anyhow!(
+                    "no map header found, expected a module name followed by a `Timestamp is` line"
+                ),
+            }
+            .into());
+        }
+
+        let mut map = parser.finish(line + 1, offset)?;
+        map.encoding = encoding;
+
+        Ok(map)
+    }
+}
+
+#[test]
+fn from_reader() {
+    let map_string = std::fs::read_to_string("csgo-x86.map").unwrap();
+    let map = crate::MapFile::load(&map_string).unwrap();
+
+    let file = std::fs::File::open("csgo-x86.map").unwrap();
+    let streamed = MapFileOwned::from_reader(std::io::BufReader::new(file)).unwrap();
+
+    assert_eq!(streamed.file_name, map.file_name);
+    assert_eq!(streamed.timestamp, map.timestamp);
+    assert_eq!(streamed.preferred_load_addr, map.preferred_load_addr);
+    assert_eq!(streamed.entrypoint, map.entrypoint);
+    assert_eq!(streamed.sections.len(), map.sections.len());
+    assert_eq!(streamed.functions.len(), map.functions.len());
+    assert_eq!(streamed.static_symbols.len(), map.static_symbols.len());
+    assert!(streamed
+        .functions
+        .iter()
+        .zip(&map.functions)
+        .all(|(a, b)| a.symbol == b.symbol && a.rva.0 == b.rva.0));
+}
+
+#[test]
+fn from_reader_exports_and_preamble() {
+    let map_string = std::fs::read_to_string("fixtures/mapinfo-exports.map").unwrap();
+
+    let streamed = MapFileOwned::from_reader(map_string.as_bytes()).unwrap();
+    assert_eq!(streamed.exports.len(), 2);
+    assert_eq!(streamed.exports[0].name.as_deref(), Some("CreateInterface"));
+
+    let map_string = std::fs::read_to_string("fixtures/linker-preamble.map").unwrap();
+
+    let streamed = MapFileOwned::from_reader(map_string.as_bytes()).unwrap();
+    assert_eq!(streamed.file_name, "linker-preamble");
+}
+
+#[test]
+fn from_reader_error_position() {
+    let map_string = std::fs::read_to_string("fixtures/mapinfo-exports.map").unwrap();
+    let map_string = map_string.replace("0001:00000040", "0001:xyz");
+
+    let loaded = crate::MapFile::load(&map_string).err().unwrap();
+    let streamed = MapFileOwned::from_reader(map_string.as_bytes())
+        .err()
+        .unwrap();
+
+    assert_eq!(streamed.line(), loaded.line());
+    assert_eq!(streamed.span(), loaded.span());
+    assert_eq!(streamed.to_string(), loaded.to_string());
+
+    let error = MapFileOwned::from_reader(&b"nothing to see here\n"[..])
+        .err()
+        .unwrap();
+    assert!(error.to_string().starts_with("no map header found"));
+}
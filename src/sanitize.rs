@@ -0,0 +1,117 @@
+/// turns a (demangled) symbol name into something the target tool accepts as a label
+pub trait NameSanitizer {
+    fn sanitize(&self, name: &str) -> String;
+}
+
+impl<F: Fn(&str) -> String> NameSanitizer for F {
+    fn sanitize(&self, name: &str) -> String {
+        self(name)
+    }
+}
+
+fn replace_unless(name: &str, allowed: impl Fn(char) -> bool) -> String {
+    name.chars()
+        .map(|x| match allowed(x) {
+            true => x,
+            false => '_',
+        })
+        .collect()
+}
+
+/// IDA only takes `_$?@` and ascii alphanumerics in names
+pub struct IdaSanitizer;
+
+impl NameSanitizer for IdaSanitizer {
+    fn sanitize(&self, name: &str) -> String {
+        replace_unless(name, |x| x.is_ascii_alphanumeric() || "_$?@".contains(x))
+    }
+}
+
+/// ghidra labels may contain pretty much anything except whitespace
+pub struct GhidraSanitizer;
+
+impl NameSanitizer for GhidraSanitizer {
+    fn sanitize(&self, name: &str) -> String {
+        replace_unless(name, |x| !x.is_whitespace())
+    }
+}
+
+/// radare2 flag names are restricted to ascii alphanumerics, `_` and `.`
+pub struct R2Sanitizer;
+
+impl NameSanitizer for R2Sanitizer {
+    fn sanitize(&self, name: &str) -> String {
+        replace_unless(name, |x| x.is_ascii_alphanumeric() || "_.".contains(x))
+    }
+}
+
+/// x64dbg labels are free text, but control characters break its database
+pub struct X64dbgSanitizer;
+
+impl NameSanitizer for X64dbgSanitizer {
+    fn sanitize(&self, name: &str) -> String {
+        replace_unless(name, |x| !x.is_control())
+    }
+}
+
+/// keeps names exactly as they are, for tools that take any string as a name
+pub struct VerbatimSanitizer;
+
+impl NameSanitizer for VerbatimSanitizer {
+    fn sanitize(&self, name: &str) -> String {
+        name.to_owned()
+    }
+}
+
+#[cfg(test)]
+const TRICKY_NAME: &str = "std::vector<int>::operator[] `anonymous namespace'::zap\t1";
+
+#[test]
+fn ida_sanitizer() {
+    assert_eq!(
+        IdaSanitizer.sanitize(TRICKY_NAME),
+        "std__vector_int___operator____anonymous_namespace___zap_1"
+    );
+    // every ascii letter is allowed, lowercase z included
+    assert_eq!(IdaSanitizer.sanitize("Zz_fizz"), "Zz_fizz");
+    assert_eq!(
+        IdaSanitizer.sanitize("??_C@_05CJBACGMB@hello?$AA@"),
+        "??_C@_05CJBACGMB@hello?$AA@"
+    );
+}
+
+#[test]
+fn ghidra_sanitizer() {
+    assert_eq!(
+        GhidraSanitizer.sanitize(TRICKY_NAME),
+        "std::vector<int>::operator[]_`anonymous_namespace'::zap_1"
+    );
+}
+
+#[test]
+fn r2_sanitizer() {
+    assert_eq!(
+        R2Sanitizer.sanitize(TRICKY_NAME),
+        "std__vector_int___operator____anonymous_namespace___zap_1"
+    );
+    assert_eq!(R2Sanitizer.sanitize("sym.imp.printf"), "sym.imp.printf");
+}
+
+#[test]
+fn x64dbg_sanitizer() {
+    assert_eq!(
+        X64dbgSanitizer.sanitize(TRICKY_NAME),
+        "std::vector<int>::operator[] `anonymous namespace'::zap_1"
+    );
+}
+
+#[test]
+fn verbatim_sanitizer() {
+    assert_eq!(VerbatimSanitizer.sanitize(TRICKY_NAME), TRICKY_NAME);
+}
+
+#[test]
+fn closure_sanitizer() {
+    let upper = |name: &str| name.to_uppercase();
+    assert_eq!(upper.sanitize("zap"), "ZAP");
+}
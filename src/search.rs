@@ -0,0 +1,134 @@
+use msvc_demangler::DemangleFlags;
+
+use crate::{MapFile, SymbolRef};
+
+/// how [`MapFile::find_symbols`] compares names with the query. the default is an exact,
+/// case sensitive match on the mangled name
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SearchOptions {
+    pub case_insensitive: bool,
+    /// match names containing the query rather than only the query itself
+    pub substring: bool,
+    /// compare with the name-only demangled form, e.g. `Foo::Release`. names that don't
+    /// demangle are compared as they are
+    pub demangled: bool,
+}
+
+impl SearchOptions {
+    fn matcher(self, query: &str) -> impl Fn(&str) -> bool + '_ {
+        let lowercase = self.case_insensitive.then(|| query.to_lowercase());
+
+        move |name| match (&lowercase, self.substring) {
+            (None, false) => name == query,
+            (None, true) => name.contains(query),
+            (Some(query), false) => name.to_lowercase() == *query,
+            (Some(query), true) => name.to_lowercase().contains(query.as_str()),
+        }
+    }
+}
+
+fn demangle_name_only(symbol: &str) -> Option<String> {
+    msvc_demangler::demangle(symbol, DemangleFlags::NAME_ONLY).ok()
+}
+
+/// every symbol with its demangled name worked out once, for running lots of searches like
+/// a search box does. get one from [`MapFile::symbol_search`]
+pub struct SymbolSearch<'m> {
+    symbols: Vec<(SymbolRef<'m>, Option<String>)>,
+}
+
+impl<'m> SymbolSearch<'m> {
+    /// [`MapFile::find_symbols`] without demangling anything again
+    pub fn find(&self, query: &str, options: SearchOptions) -> Vec<SymbolRef<'m>> {
+        let matches = options.matcher(query);
+
+        self.symbols
+            .iter()
+            .filter(|(symbol, demangled)| match (options.demangled, demangled) {
+                (true, Some(demangled)) => matches(demangled),
+                _ => matches(symbol.symbol()),
+            })
+            .map(|(symbol, _)| *symbol)
+            .collect()
+    }
+}
+
+impl<'a> MapFile<'a> {
+    /// publics, then statics, whose name matches `query` per `options`, in map order
+    pub fn find_symbols(&self, query: &str, options: SearchOptions) -> Vec<SymbolRef<'_>> {
+        let matches = options.matcher(query);
+
+        self.symbols()
+            .filter(|symbol| match options.demangled {
+                true => match demangle_name_only(symbol.symbol()) {
+                    Some(demangled) => matches(&demangled),
+                    None => matches(symbol.symbol()),
+                },
+                false => matches(symbol.symbol()),
+            })
+            .collect()
+    }
+
+    /// demangles every symbol once for repeated [`SymbolSearch::find`] calls
+    pub fn symbol_search(&self) -> SymbolSearch<'_> {
+        SymbolSearch {
+            symbols: self
+                .symbols()
+                .map(|symbol| (symbol, demangle_name_only(symbol.symbol())))
+                .collect(),
+        }
+    }
+}
+
+#[test]
+fn find_symbols() {
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+    let search = map.symbol_search();
+
+    let find = |query: &str, options: SearchOptions| -> Vec<&str> {
+        let found: Vec<&str> = map
+            .find_symbols(query, options)
+            .iter()
+            .map(|x| x.symbol())
+            .collect();
+
+        let cached: Vec<&str> = search
+            .find(query, options)
+            .iter()
+            .map(|x| x.symbol())
+            .collect();
+        assert_eq!(found, cached);
+
+        found
+    };
+
+    assert_eq!(find("_init", SearchOptions::default()), ["_init"]);
+    assert!(find("_INIT", SearchOptions::default()).is_empty());
+
+    let options = SearchOptions {
+        case_insensitive: true,
+        ..Default::default()
+    };
+    assert_eq!(find("_INIT", options), ["_init"]);
+
+    let options = SearchOptions {
+        substring: true,
+        ..Default::default()
+    };
+    assert_eq!(find("_local", options), ["_local_helper"]);
+    assert_eq!(
+        find("Release", options),
+        ["?Release@Foo@@UAEXXZ", "?Release@Bar@@UAEXXZ"]
+    );
+
+    let options = SearchOptions {
+        case_insensitive: true,
+        substring: true,
+        demangled: true,
+    };
+    assert_eq!(find("bar::", options), ["?Release@Bar@@UAEXXZ"]);
+    // names that don't demangle are matched as they are
+    assert_eq!(find("SHUTDOWN", options), ["_shutdown"]);
+}
@@ -0,0 +1,86 @@
+use std::collections::HashSet;
+
+use crate::{Class, LibObject, MapFile};
+
+/// counts for logging and reports, see [`MapFile::summary`]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MapSummary {
+    /// every section, whatever its class
+    pub sections: usize,
+    /// `CODE` sections
+    pub code_sections: usize,
+    /// `DATA` sections
+    pub data_sections: usize,
+    pub functions: usize,
+    pub static_symbols: usize,
+    /// distinct `(lib, obj)` pairs any public or static came from
+    pub objects: usize,
+    /// publics and statics in segment 0
+    pub absolute_symbols: usize,
+    /// lowest and highest rva of a placed symbol, `None` if there is none
+    pub rva_span: Option<(usize, usize)>,
+}
+
+impl<'a> MapFile<'a> {
+    /// the usual counts in one pass, e.g. for "parsed N functions across M objects"
+    pub fn summary(&self) -> MapSummary {
+        let mut summary = MapSummary {
+            sections: self.sections.len(),
+            functions: self.functions.len(),
+            static_symbols: self.static_symbols.len(),
+            ..Default::default()
+        };
+
+        for section in &self.sections {
+            match section.class {
+                Class::Code => summary.code_sections += 1,
+                Class::Data => summary.data_sections += 1,
+                _ => {}
+            }
+        }
+
+        let mut objects = HashSet::new();
+
+        for symbol in self.symbols() {
+            if let LibObject::LibObj(lib, obj) = symbol.libobj() {
+                objects.insert((lib.as_deref(), &**obj));
+            }
+
+            if symbol.addr().is_absolute() {
+                summary.absolute_symbols += 1;
+            }
+
+            if symbol.rva() != 0 {
+                let (low, high) = summary.rva_span.get_or_insert((symbol.rva(), symbol.rva()));
+                *low = (*low).min(symbol.rva());
+                *high = (*high).max(symbol.rva());
+            }
+        }
+
+        summary.objects = objects.len();
+
+        summary
+    }
+}
+
+#[test]
+fn summary() {
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    assert_eq!(
+        map.summary(),
+        MapSummary {
+            sections: 3,
+            code_sections: 2,
+            data_sections: 1,
+            functions: 6,
+            static_symbols: 1,
+            objects: 3,
+            absolute_symbols: 0,
+            rva_span: Some((0x1000, 0x3010)),
+        }
+    );
+}
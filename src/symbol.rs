@@ -0,0 +1,331 @@
+use alloc::vec::Vec;
+use core::iter::Peekable;
+
+use crate::{Address, Function, LibObject, MapFile, StaticSymbol, Symbol, SymbolFlags};
+
+/// either kind of symbol, for code that doesn't care whether it was public or static
+#[derive(Debug, Clone, Copy)]
+pub enum SymbolRef<'m> {
+    Function(&'m Function<'m>),
+    Static(&'m StaticSymbol<'m>),
+}
+
+impl<'m> SymbolRef<'m> {
+    /// the symbol itself, whichever table it came from
+    pub fn get(&self) -> &'m Symbol<'m> {
+        match self {
+            SymbolRef::Function(x) | SymbolRef::Static(x) => x,
+        }
+    }
+
+    pub fn symbol(&self) -> &'m str {
+        &self.get().symbol
+    }
+
+    pub fn addr(&self) -> &'m Address {
+        &self.get().addr
+    }
+
+    pub fn rva(&self) -> usize {
+        self.get().rva.0
+    }
+
+    pub fn flags(&self) -> SymbolFlags {
+        self.get().flags
+    }
+
+    pub fn libobj(&self) -> &'m LibObject<'m> {
+        &self.get().libobj
+    }
+
+    pub fn rva_plus_base(&self, map: &MapFile) -> usize {
+        self.get().rva_plus_base(map)
+    }
+
+    pub fn va(&self, map: &MapFile) -> usize {
+        self.get().va(map)
+    }
+}
+
+/// lazily merges two rva-sorted symbol streams into one rva-sorted stream.
+/// on ties the left side comes first
+pub struct MergeByRva<L: Iterator, R: Iterator> {
+    left: Peekable<L>,
+    right: Peekable<R>,
+}
+
+impl<L: Iterator, R: Iterator> MergeByRva<L, R> {
+    #[cfg(feature = "std")]
+    pub(crate) fn new(left: L, right: R) -> Self {
+        MergeByRva {
+            left: left.peekable(),
+            right: right.peekable(),
+        }
+    }
+}
+
+impl<'m, L, R> Iterator for MergeByRva<L, R>
+where
+    L: Iterator<Item = SymbolRef<'m>>,
+    R: Iterator<Item = SymbolRef<'m>>,
+{
+    type Item = SymbolRef<'m>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match (self.left.peek(), self.right.peek()) {
+            (Some(left), Some(right)) if right.rva() < left.rva() => self.right.next(),
+            (Some(_), _) => self.left.next(),
+            (None, _) => self.right.next(),
+        }
+    }
+}
+
+impl<'a> MapFile<'a> {
+    /// every public followed by every static symbol. use [`MapFile::symbols_by_rva`] if the
+    /// order matters
+    pub fn symbols(&self) -> impl Iterator<Item = SymbolRef<'_>> {
+        self.functions
+            .iter()
+            .map(SymbolRef::Function)
+            .chain(self.static_symbols.iter().map(SymbolRef::Static))
+    }
+
+    /// publics and static symbols interleaved by rva, without collecting them first.
+    ///
+    /// relies on both tables already being sorted, which is how link.exe writes them
+    pub fn symbols_by_rva(
+        &self,
+    ) -> MergeByRva<impl Iterator<Item = SymbolRef<'_>>, impl Iterator<Item = SymbolRef<'_>>> {
+        MergeByRva {
+            left: self.functions.iter().map(SymbolRef::Function).peekable(),
+            right: self.static_symbols.iter().map(SymbolRef::Static).peekable(),
+        }
+    }
+
+    /// publics and statics with an rva in `start..end`, in address order. binary searches both
+    /// tables, so like [`MapFile::symbols_by_rva`] it relies on them being sorted
+    pub fn symbols_in_range(
+        &self,
+        start: usize,
+        end: usize,
+    ) -> impl Iterator<Item = SymbolRef<'_>> {
+        let end = end.max(start);
+
+        let publics = &self.functions[self.functions.partition_point(|x| x.rva.0 < start)
+            ..self.functions.partition_point(|x| x.rva.0 < end)];
+        let statics = &self.static_symbols[self.static_symbols.partition_point(|x| x.rva.0 < start)
+            ..self.static_symbols.partition_point(|x| x.rva.0 < end)];
+
+        MergeByRva {
+            left: publics.iter().map(SymbolRef::Function).peekable(),
+            right: statics.iter().map(SymbolRef::Static).peekable(),
+        }
+    }
+
+    /// panics if the publics or statics are no longer sorted by rva, which everything built on
+    /// [`MapFile::symbols_by_rva`] relies on. handy after editing the tables by hand
+    pub fn assert_sorted(&self) {
+        if let Some(idx) = self
+            .functions
+            .windows(2)
+            .position(|x| x[0].rva.0 > x[1].rva.0)
+        {
+            panic!(
+                "publics are not sorted by rva: {} comes after {}",
+                self.functions[idx + 1].symbol,
+                self.functions[idx].symbol
+            );
+        }
+
+        if let Some(idx) = self
+            .static_symbols
+            .windows(2)
+            .position(|x| x[0].rva.0 > x[1].rva.0)
+        {
+            panic!(
+                "static symbols are not sorted by rva: {} comes after {}",
+                self.static_symbols[idx + 1].symbol,
+                self.static_symbols[idx].symbol
+            );
+        }
+    }
+
+    /// the closest symbol below `rva`, i.e. the one before it in [`MapFile::symbols_by_rva`] order
+    pub fn prev_symbol(&self, rva: usize) -> Option<SymbolRef<'_>> {
+        let publics = &self.functions[..self.functions.partition_point(|x| x.rva.0 < rva)];
+        let statics =
+            &self.static_symbols[..self.static_symbols.partition_point(|x| x.rva.0 < rva)];
+
+        match (publics.last(), statics.last()) {
+            (Some(public), Some(static_)) if public.rva.0 > static_.rva.0 => {
+                Some(SymbolRef::Function(public))
+            }
+            (_, Some(static_)) => Some(SymbolRef::Static(static_)),
+            (public, None) => public.map(SymbolRef::Function),
+        }
+    }
+
+    /// the closest symbol above `rva`, i.e. the one after it in [`MapFile::symbols_by_rva`] order
+    pub fn next_symbol(&self, rva: usize) -> Option<SymbolRef<'_>> {
+        let publics = &self.functions[self.functions.partition_point(|x| x.rva.0 <= rva)..];
+        let statics =
+            &self.static_symbols[self.static_symbols.partition_point(|x| x.rva.0 <= rva)..];
+
+        match (publics.first(), statics.first()) {
+            (Some(public), Some(static_)) if static_.rva.0 < public.rva.0 => {
+                Some(SymbolRef::Static(static_))
+            }
+            (Some(public), _) => Some(SymbolRef::Function(public)),
+            (None, static_) => static_.map(SymbolRef::Static),
+        }
+    }
+
+    /// the publics sorted by rva, then name. unlike the table itself the order doesn't depend on
+    /// how the linker happened to list aliases, so exports stay byte-identical between runs
+    pub fn sorted_functions(&self) -> Vec<&Function<'a>> {
+        sorted_by_rva(&self.functions)
+    }
+
+    /// the static symbols sorted like [`MapFile::sorted_functions`]
+    pub fn sorted_static_symbols(&self) -> Vec<&StaticSymbol<'a>> {
+        sorted_by_rva(&self.static_symbols)
+    }
+}
+
+fn sorted_by_rva<'s, 'a>(symbols: &'s [Symbol<'a>]) -> Vec<&'s Symbol<'a>> {
+    let mut symbols: Vec<&Symbol<'a>> = symbols.iter().collect();
+    // stable, so exact duplicates keep their map order
+    symbols.sort_by(|x, y| (x.rva.0, &x.symbol).cmp(&(y.rva.0, &y.symbol)));
+    symbols
+}
+
+#[test]
+fn symbols_by_rva() {
+    let map_data = std::fs::read("csgo-x86.map").unwrap();
+    let map_string = String::from_utf8(map_data).unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let merged: Vec<SymbolRef> = map.symbols_by_rva().collect();
+
+    assert_eq!(merged.len(), map.functions.len() + map.static_symbols.len());
+    assert!(merged.windows(2).all(|x| x[0].rva() <= x[1].rva()));
+    assert!(merged.iter().any(|x| matches!(x, SymbolRef::Static(_))));
+}
+
+#[test]
+fn prev_next_symbol() {
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    assert!(map.prev_symbol(0x1000).is_none());
+    assert_eq!(
+        map.next_symbol(0x1000).unwrap().symbol(),
+        "?Release@Foo@@UAEXXZ"
+    );
+
+    // statics are interleaved with the publics
+    assert_eq!(map.prev_symbol(0x1080).unwrap().symbol(), "_local_helper");
+    assert_eq!(map.next_symbol(0x1040).unwrap().symbol(), "_local_helper");
+    assert_eq!(
+        map.prev_symbol(0x1060).unwrap().symbol(),
+        "?Release@Bar@@UAEXXZ"
+    );
+
+    // rvas between symbols work too
+    assert_eq!(map.next_symbol(0x1070).unwrap().symbol(), "_shutdown");
+
+    assert_eq!(map.prev_symbol(0x4000).unwrap().symbol(), "_g_state");
+    assert!(map.next_symbol(0x3010).is_none());
+}
+
+#[test]
+fn assert_sorted() {
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+
+    let mut map = MapFile::load(&map_string).unwrap();
+    map.assert_sorted();
+
+    map.functions.retain(|x| x.symbol != "_shutdown");
+    map.assert_sorted();
+}
+
+#[test]
+#[should_panic(expected = "publics are not sorted by rva: ?Release@Foo@@UAEXXZ comes after _init")]
+fn assert_sorted_detects_mutation() {
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+
+    let mut map = MapFile::load(&map_string).unwrap();
+    map.functions[0].rva.0 = 0x1050;
+    map.assert_sorted();
+}
+
+#[test]
+fn symbols() {
+    let map_string = std::fs::read_to_string("fixtures/mapinfo-exports.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let symbols: Vec<SymbolRef> = map.symbols().collect();
+    assert_eq!(symbols.len(), 7);
+    assert_eq!(symbols[1].symbol(), "_DllMain@12");
+    assert!(symbols[1].flags().contains(SymbolFlags::IS_FUNCTION));
+    assert!(matches!(symbols[0].libobj(), LibObject::Absolute));
+
+    let last = symbols.last().unwrap();
+    assert!(matches!(last, SymbolRef::Static(_)));
+    assert_eq!(last.symbol(), "_static_helper");
+    assert_eq!(last.rva(), 0x1200);
+    assert_eq!(last.va(&map), 0x10001200);
+}
+
+#[test]
+fn symbols_in_range() {
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let names: Vec<&str> = map
+        .symbols_in_range(0x1040, 0x1100)
+        .map(|x| x.symbol())
+        .collect();
+    assert_eq!(
+        names,
+        [
+            "?Release@Foo@@UAEXXZ",
+            "?Release@Bar@@UAEXXZ",
+            "_local_helper",
+            "_shutdown"
+        ]
+    );
+
+    assert_eq!(map.symbols_in_range(0x1100, 0x1040).count(), 0);
+    assert_eq!(
+        map.symbols_in_range(0, usize::MAX).count(),
+        map.symbols().count()
+    );
+}
+
+#[test]
+fn sorted_symbols() {
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+
+    let mut map = MapFile::load(&map_string).unwrap();
+    map.functions.reverse();
+
+    let names: Vec<&str> = map.sorted_functions().iter().map(|x| &*x.symbol).collect();
+    assert_eq!(
+        names,
+        [
+            "_init",
+            "?Release@Bar@@UAEXXZ",
+            "?Release@Foo@@UAEXXZ",
+            "_shutdown",
+            "_unwind",
+            "_g_state"
+        ]
+    );
+
+    assert_eq!(map.sorted_static_symbols()[0].symbol, "_local_helper");
+}
@@ -0,0 +1,162 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::{Address, MapFile};
+
+/// something structurally wrong with a map, usually from corruption or hand editing
+#[derive(Debug, PartialEq, Eq)]
+pub enum ValidationIssue<'m> {
+    /// two sections in the same segment claim the same bytes. indices are into `sections`
+    OverlappingSections { first: usize, second: usize },
+    /// symbols refer to a segment no section is in, e.g. in a map cut off inside the section
+    /// table. those symbols aren't reported one by one as [`SymbolOutsideSection`]
+    ///
+    /// [`SymbolOutsideSection`]: ValidationIssue::SymbolOutsideSection
+    OrphanSegment { seg: u32, symbols: usize },
+    /// a symbol's address isn't covered by any section of its segment
+    SymbolOutsideSection { symbol: &'m str, addr: &'m Address },
+    /// more than one public goes by this name, e.g. after merging maps of different link
+    /// steps. statics are left out, as every object may have its own
+    DuplicateSymbol { symbol: &'m str, count: usize },
+}
+
+impl<'a> MapFile<'a> {
+    /// checks the map for inconsistencies that would silently break the address lookups,
+    /// collecting every issue rather than stopping at the first
+    pub fn validate(&self) -> Result<(), Vec<ValidationIssue<'_>>> {
+        let mut issues = Vec::new();
+
+        for (first, a) in self.sections.iter().enumerate() {
+            for (second, b) in self.sections.iter().enumerate().skip(first + 1) {
+                if a.addr.seg == b.addr.seg && a.addr.addr < b.end() && b.addr.addr < a.end() {
+                    issues.push(ValidationIssue::OverlappingSections { first, second });
+                }
+            }
+        }
+
+        let segments: HashSet<u32> = self.sections.iter().map(|x| x.addr.seg).collect();
+        let mut orphans: BTreeMap<u32, usize> = BTreeMap::new();
+
+        for symbol in self.symbols() {
+            let seg = symbol.addr().seg;
+
+            // segment 0 holds <absolute> symbols, which aren't in any section
+            if seg != 0 && !segments.contains(&seg) {
+                *orphans.entry(seg).or_default() += 1;
+            }
+        }
+
+        for (&seg, &symbols) in &orphans {
+            issues.push(ValidationIssue::OrphanSegment { seg, symbols });
+        }
+
+        for symbol in self.symbols_by_rva() {
+            if symbol.addr().is_absolute() || orphans.contains_key(&symbol.addr().seg) {
+                continue;
+            }
+
+            if self.section_containing(symbol.addr()).is_none() {
+                issues.push(ValidationIssue::SymbolOutsideSection {
+                    symbol: symbol.symbol(),
+                    addr: symbol.addr(),
+                });
+            }
+        }
+
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+
+        for function in &self.functions {
+            *counts.entry(&function.symbol).or_default() += 1;
+        }
+
+        // in map order, so the result doesn't depend on the hash map's
+        for function in &self.functions {
+            if let Some(count) = counts.remove(&*function.symbol).filter(|&x| x > 1) {
+                issues.push(ValidationIssue::DuplicateSymbol {
+                    symbol: &function.symbol,
+                    count,
+                });
+            }
+        }
+
+        match issues.is_empty() {
+            true => Ok(()),
+            false => Err(issues),
+        }
+    }
+}
+
+#[test]
+fn validate_overlap() {
+    let map_string = std::fs::read_to_string("fixtures/overlap.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    let issues = map.validate().unwrap_err();
+
+    assert_eq!(issues.len(), 2);
+    assert_eq!(
+        issues[0],
+        ValidationIssue::OverlappingSections {
+            first: 0,
+            second: 1
+        }
+    );
+    assert!(matches!(
+        issues[1],
+        ValidationIssue::SymbolOutsideSection {
+            symbol: "_g_stray",
+            ..
+        }
+    ));
+}
+
+#[test]
+fn validate_duplicate_symbols() {
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+    let map_string = map_string
+        .replace("?Release@Bar@@UAEXXZ", "?Release@Foo@@UAEXXZ")
+        .replace("_unwind   ", "_init     ");
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    assert_eq!(
+        map.validate(),
+        Err(vec![
+            ValidationIssue::DuplicateSymbol {
+                symbol: "_init",
+                count: 2
+            },
+            ValidationIssue::DuplicateSymbol {
+                symbol: "?Release@Foo@@UAEXXZ",
+                count: 2
+            },
+        ])
+    );
+}
+
+#[test]
+fn validate_clean() {
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    assert_eq!(map.validate(), Ok(()));
+}
+
+#[test]
+fn validate_orphan_segments() {
+    let map_string = std::fs::read_to_string("fixtures/aliases.map").unwrap();
+    let map_string = map_string
+        .replace(
+            " 0002:00000010       _g_state",
+            " 0005:00000010       _g_state",
+        )
+        .replace(" 0001:00000060", " 0005:00000060");
+
+    let map = MapFile::load(&map_string).unwrap();
+
+    assert_eq!(
+        map.validate(),
+        Err(vec![ValidationIssue::OrphanSegment { seg: 5, symbols: 2 }])
+    );
+}
@@ -0,0 +1,12 @@
+use wasm_bindgen::prelude::*;
+
+use crate::MapFile;
+
+/// parses a map and hands it to js as the json serialization of [`crate::MapFileOwned`].
+/// parse errors are thrown as an `Error` with the [`crate::ParseError`] message
+#[wasm_bindgen(js_name = parseMap)]
+pub fn parse_map(input: &str) -> Result<JsValue, JsError> {
+    let map = MapFile::parse(input)?;
+
+    Ok(JsValue::from_str(&serde_json::to_string(&map)?))
+}